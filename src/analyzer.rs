@@ -0,0 +1,85 @@
+use ureq::http::{HeaderMap, Uri};
+
+use crate::report::Report;
+use crate::{
+    bluesky, github, instagram, json_ld, mastodon, opengraph, readability, reddit,
+    telegram_preview, tiktok, twitter, wikipedia, youtube,
+};
+
+/// All known analyzers.
+///
+/// New site modules only need to be added here in order to be run by [`crate::inspect_url`].
+static ANALYZERS: &[&dyn Analyzer] = &[
+    &opengraph::OpenGraph,
+    &json_ld::JsonLd,
+    &bluesky::Bluesky,
+    &github::GitHub,
+    &instagram::Instagram,
+    &mastodon::Mastodon,
+    &readability::Readability,
+    &reddit::Reddit,
+    &telegram_preview::TelegramPreview,
+    &tiktok::TikTok,
+    &twitter::Twitter,
+    &wikipedia::Wikipedia,
+    &youtube::YouTube,
+];
+
+/// The already fetched page handed to each [`Analyzer`]
+pub struct Page<'page> {
+    /// Final URI after following redirects
+    pub uri: &'page Uri,
+    pub headers: &'page HeaderMap,
+    pub body: &'page str,
+}
+
+/// Site specific analysis of a fetched page
+pub trait Analyzer: Sync {
+    /// Human readable name used in listings and error messages
+    fn name(&self) -> &'static str;
+
+    /// Whether this analyzer is interested in the page.
+    ///
+    /// Should be cheap as it is checked for every page. Most analyzers only check the URI.
+    fn matches(&self, page: &Page) -> bool;
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report>;
+}
+
+/// Names of all registered analyzers
+pub fn names() -> impl Iterator<Item = &'static str> {
+    ANALYZERS.iter().map(|analyzer| analyzer.name())
+}
+
+/// All analyzers interested in the given page. There might be multiple.
+pub fn matching<'page>(
+    page: &'page Page<'page>,
+) -> impl Iterator<Item = &'static dyn Analyzer> + 'page {
+    ANALYZERS
+        .iter()
+        .copied()
+        .filter(move |analyzer| analyzer.matches(page))
+}
+
+/// Check whether the host of the URI is the domain or a subdomain of it
+pub fn host_is(uri: &Uri, domain: &str) -> bool {
+    uri.host().is_some_and(|host| {
+        host == domain
+            || host
+                .strip_suffix(domain)
+                .is_some_and(|subdomain| subdomain.ends_with('.'))
+    })
+}
+
+#[test]
+fn host_is_works() {
+    let uri = Uri::from_static("https://www.tiktok.com/@user/video/123");
+    assert!(host_is(&uri, "tiktok.com"));
+    assert!(host_is(&uri, "www.tiktok.com"));
+    assert!(!host_is(&uri, "ktok.com"));
+    assert!(!host_is(
+        &Uri::from_static("https://nottiktok.com/"),
+        "tiktok.com"
+    ));
+    assert!(!host_is(&Uri::from_static("/relative"), "tiktok.com"));
+}
@@ -0,0 +1,323 @@
+use std::fmt::Write as _;
+
+use chrono::DateTime;
+use serde_json::Value;
+
+use crate::analyzer::{Analyzer, Page, host_is};
+use crate::json::deep_get;
+use crate::report::{Photo, Report};
+
+/// Public XRPC API which does not need authentication. Can be replaced with `BSKY_API`.
+const DEFAULT_API: &str = "https://public.api.bsky.app";
+
+pub struct Bluesky;
+
+impl Analyzer for Bluesky {
+    fn name(&self) -> &'static str {
+        "bluesky"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        host_is(page.uri, "bsky.app") && post_path(page.uri.path()).is_some()
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        let (handle, rkey) = post_path(page.uri.path()).expect("matches ensures a post path");
+        let api = std::env::var("BSKY_API").unwrap_or_else(|_| DEFAULT_API.to_owned());
+        let post = fetch_post(&api, handle, rkey)?;
+        Ok(analyze(&post))
+    }
+}
+
+/// Handle (or DID) and record key of `/profile/<handle>/post/<rkey>`
+fn post_path(path: &str) -> Option<(&str, &str)> {
+    let mut segments = path.split('/').filter(|segment| !segment.is_empty());
+    let (Some("profile"), Some(handle), Some("post"), Some(rkey), None) = (
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+        segments.next(),
+    ) else {
+        return None;
+    };
+    Some((handle, rkey))
+}
+
+fn fetch_post(api: &str, handle: &str, rkey: &str) -> anyhow::Result<Value> {
+    let did = if handle.starts_with("did:") {
+        handle.to_owned()
+    } else {
+        let resolved = crate::http::get_json(
+            &format!("{api}/xrpc/com.atproto.identity.resolveHandle?handle={handle}"),
+            &[],
+        )?;
+        resolved
+            .get("did")
+            .and_then(Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Handle {handle} should resolve to a DID"))?
+            .to_owned()
+    };
+    let mut posts = crate::http::get_json(
+        &format!("{api}/xrpc/app.bsky.feed.getPosts?uris=at://{did}/app.bsky.feed.post/{rkey}"),
+        &[],
+    )?;
+    posts
+        .get_mut("posts")
+        .and_then(|posts| posts.get_mut(0))
+        .map(Value::take)
+        .ok_or_else(|| anyhow::anyhow!("Post {rkey} of {did} should exist"))
+}
+
+fn analyze(post: &Value) -> Report {
+    let mut report = Report::default();
+    let record = post.get("record").unwrap_or(&Value::Null);
+    let text = record
+        .get("text")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    report.text(Some(&author(post)), text);
+
+    let mut info = String::new();
+    if let Some(time) = record
+        .get("createdAt")
+        .and_then(Value::as_str)
+        .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+    {
+        writeln!(info, "createdAt: {}", time.to_utc()).unwrap();
+    }
+    for key in ["likeCount", "repostCount", "replyCount", "quoteCount"] {
+        if let Some(count) = post.get(key).and_then(Value::as_u64) {
+            writeln!(info, "{key}: {count}").unwrap();
+        }
+    }
+    report.text(None, info);
+
+    let facets = facets(text, record);
+    if !facets.is_empty() {
+        report.text(Some("facets"), facets.join("\n"));
+    }
+
+    let mut photos = Vec::new();
+    if let Some(embed) = post.get("embed") {
+        describe_embed(&mut report, &mut photos, embed);
+    }
+
+    report.insert_data("uri", post.get("uri").cloned());
+    report.insert_data("author", post.get("author").cloned());
+    report.insert_data("text", text);
+    report.insert_data("createdAt", record.get("createdAt").cloned());
+    report.insert_data("facets", facets);
+    report.insert_data("embed", post.get("embed").cloned());
+    for key in ["likeCount", "repostCount", "replyCount", "quoteCount"] {
+        report.insert_data(key, post.get(key).cloned());
+    }
+
+    report.photos(photos);
+    report
+}
+
+fn author(post: &Value) -> String {
+    let handle = deep_get(post, ["author", "handle"])
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    let name = deep_get(post, ["author", "displayName"])
+        .and_then(Value::as_str)
+        .filter(|name| !name.is_empty());
+    name.map_or_else(
+        || format!("@{handle}"),
+        |name| format!("@{handle} ({name})"),
+    )
+}
+
+/// Links, mentions and tags of the text. The indices are byte offsets of the UTF-8 text.
+fn facets(text: &str, record: &Value) -> Vec<String> {
+    record
+        .get("facets")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .flat_map(|facet| {
+            let start = deep_get(facet, ["index", "byteStart"]).and_then(Value::as_u64);
+            let end = deep_get(facet, ["index", "byteEnd"]).and_then(Value::as_u64);
+            let slice = start
+                .zip(end)
+                .and_then(|(start, end)| {
+                    text.as_bytes()
+                        .get(usize::try_from(start).ok()?..usize::try_from(end).ok()?)
+                })
+                .map(String::from_utf8_lossy)
+                .unwrap_or_default();
+            facet
+                .get("features")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(move |feature| {
+                    let kind = feature.get("$type")?.as_str()?;
+                    let target = match kind {
+                        "app.bsky.richtext.facet#link" => feature.get("uri"),
+                        "app.bsky.richtext.facet#mention" => feature.get("did"),
+                        "app.bsky.richtext.facet#tag" => feature.get("tag"),
+                        _ => None,
+                    }?
+                    .as_str()?;
+                    let kind = kind.trim_start_matches("app.bsky.richtext.facet#");
+                    Some(format!("{kind}: {slice} → {target}"))
+                })
+        })
+        .collect()
+}
+
+fn describe_embed(report: &mut Report, photos: &mut Vec<Photo>, embed: &Value) {
+    let kind = embed
+        .get("$type")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    match kind {
+        "app.bsky.embed.images#view" => {
+            for image in embed
+                .get("images")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                if let Some(url) = image.get("fullsize").and_then(Value::as_str) {
+                    photos.push(Photo {
+                        url: url.to_owned(),
+                        caption: image
+                            .get("alt")
+                            .and_then(Value::as_str)
+                            .filter(|alt| !alt.is_empty())
+                            .map(ToOwned::to_owned),
+                    });
+                }
+            }
+        }
+        "app.bsky.embed.external#view" => {
+            let external = embed.get("external").unwrap_or(&Value::Null);
+            let field = |key| {
+                external
+                    .get(key)
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+            };
+            report.text(
+                Some("link card"),
+                format!(
+                    "{}\n{}\n\n{}",
+                    field("title"),
+                    field("uri"),
+                    field("description")
+                )
+                .trim(),
+            );
+        }
+        "app.bsky.embed.record#view" => {
+            let record = embed.get("record").unwrap_or(&Value::Null);
+            let text = deep_get(record, ["value", "text"])
+                .and_then(Value::as_str)
+                .unwrap_or_default();
+            report.text(Some(&format!("quoting {}", author(record))), text);
+        }
+        "app.bsky.embed.recordWithMedia#view" => {
+            if let Some(record) = embed.get("record") {
+                describe_embed(report, photos, record);
+            }
+            if let Some(media) = embed.get("media") {
+                describe_embed(report, photos, media);
+            }
+        }
+        "app.bsky.embed.video#view" => {
+            // yt-dlp downloads the video of the post
+            report.text(None, "Contains a video, use /video to download it");
+        }
+        _ => {}
+    }
+}
+
+#[test]
+fn post_path_works() {
+    assert_eq!(
+        post_path("/profile/alice.bsky.social/post/3lg2abcdefg2x"),
+        Some(("alice.bsky.social", "3lg2abcdefg2x"))
+    );
+    assert_eq!(post_path("/profile/alice.bsky.social"), None);
+    assert_eq!(post_path("/profile/alice.bsky.social/post/"), None);
+}
+
+#[test]
+fn post_via_stand_in_api() {
+    use crate::report::Section;
+    use crate::test_server::{Route, TestServer};
+
+    let server = TestServer::new();
+    let did = "did:plc:abcdefghijklmnop";
+    let post = serde_json::json!({"posts": [{
+        "uri": format!("at://{did}/app.bsky.feed.post/3lg2abcdefg2x"),
+        "author": {"did": did, "handle": "alice.bsky.social", "displayName": "Alice"},
+        "record": {
+            "$type": "app.bsky.feed.post",
+            "createdAt": "2025-01-19T16:53:21.000Z",
+            "text": "Hiking with @bob.bsky.social 🥾 see example.com #harz",
+            "facets": [
+                {"index": {"byteStart": 12, "byteEnd": 28}, "features": [{"$type": "app.bsky.richtext.facet#mention", "did": "did:plc:bob"}]},
+                {"index": {"byteStart": 38, "byteEnd": 49}, "features": [{"$type": "app.bsky.richtext.facet#link", "uri": "https://example.com/"}]},
+                {"index": {"byteStart": 50, "byteEnd": 55}, "features": [{"$type": "app.bsky.richtext.facet#tag", "tag": "harz"}]},
+            ],
+        },
+        "embed": {
+            "$type": "app.bsky.embed.images#view",
+            "images": [
+                {"thumb": "https://cdn.bsky.app/thumb/1.jpg", "fullsize": "https://cdn.bsky.app/full/1.jpg", "alt": "Summit cross"},
+                {"thumb": "https://cdn.bsky.app/thumb/2.jpg", "fullsize": "https://cdn.bsky.app/full/2.jpg", "alt": ""},
+            ],
+        },
+        "likeCount": 42,
+        "repostCount": 5,
+        "replyCount": 3,
+        "quoteCount": 1,
+    }]});
+    let api = server.serve(vec![
+        Route::json(
+            "/xrpc/com.atproto.identity.resolveHandle",
+            format!(r#"{{"did":"{did}"}}"#),
+        ),
+        Route::json(
+            &format!(
+                "/xrpc/app.bsky.feed.getPosts?uris=at://{did}/app.bsky.feed.post/3lg2abcdefg2x"
+            ),
+            post.to_string(),
+        ),
+    ]);
+
+    let post = fetch_post(&api, "alice.bsky.social", "3lg2abcdefg2x").unwrap();
+    let report = analyze(&post);
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text }
+            if header == "@alice.bsky.social (Alice)" && text.starts_with("Hiking with")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: None, text }
+            if text.contains("createdAt: 2025-01-19 16:53:21 UTC") && text.contains("likeCount: 42")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text }
+            if header == "facets"
+                && text == "mention: @bob.bsky.social → did:plc:bob\nlink: example.com → https://example.com/\ntag: #harz → harz"
+    )));
+    let photos = report
+        .sections
+        .iter()
+        .find_map(|section| match section {
+            Section::PhotoGroup(photos) => Some(photos),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(photos[0].caption.as_deref(), Some("Summit cross"));
+    assert_eq!(photos[1].caption, None);
+}
@@ -0,0 +1,212 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+
+use crate::json_document::JsonDocument;
+use crate::report::{Report, Section, Video};
+
+pub const USAGE: &str = "Usage:
+  quickscrape-tgbot                                     Run the Telegram bot (needs BOT_TOKEN)
+  quickscrape-tgbot inspect <url> [--media-dir <dir>] [--json]
+                                                        Inspect the url and print the report";
+
+struct Args {
+    url: String,
+    /// Keep downloaded media in this directory instead of discarding it
+    media_dir: Option<PathBuf>,
+    /// Print the [`JsonDocument`] instead of the human readable report
+    json: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> anyhow::Result<Self> {
+        let mut url = None;
+        let mut media_dir = None;
+        let mut json = false;
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--media-dir" => {
+                    let dir = args.next().context("--media-dir needs a directory")?;
+                    media_dir = Some(PathBuf::from(dir));
+                }
+                "--json" => json = true,
+                _ if arg.starts_with('-') => anyhow::bail!("Unknown option {arg}"),
+                _ if url.is_none() => url = Some(arg),
+                _ => anyhow::bail!("Only a single url can be inspected at a time"),
+            }
+        }
+        Ok(Self {
+            url: url.context("Should have an url to inspect")?,
+            media_dir,
+            json,
+        })
+    }
+}
+
+/// Inspect the url given in the arguments and print the reports to stdout
+pub fn inspect(args: impl Iterator<Item = String>) -> anyhow::Result<()> {
+    let args = Args::parse(args)?;
+    if let Some(media_dir) = &args.media_dir {
+        std::fs::create_dir_all(media_dir).context("Should be able to create media dir")?;
+    }
+
+    // yt-dlp assumes every file in its directory is its output so use a fresh one
+    let download_dir = tempfile::tempdir().context("Should be able to create tempdir")?;
+    let mut document = JsonDocument::new(&args.url);
+    crate::inspect_url(
+        &args.url,
+        crate::Parts::DEFAULT,
+        download_dir.path(),
+        &mut |stage, mut report| {
+            if let Some(media_dir) = &args.media_dir {
+                keep_media(&mut report, media_dir)?;
+            }
+            if args.json {
+                document.add(stage, &report);
+            } else {
+                print!("{}", render(&report));
+            }
+            Ok(())
+        },
+    )?;
+    if args.json {
+        println!("{}", document.to_string_pretty());
+    }
+    Ok(())
+}
+
+/// Move the media of the report into the directory and update the paths accordingly
+fn keep_media(report: &mut Report, media_dir: &Path) -> anyhow::Result<()> {
+    for section in &mut report.sections {
+        let (Section::Video(Video { path, .. }) | Section::AudioFile(path)) = section else {
+            continue;
+        };
+        let target = media_dir.join(path.file_name().context("media without name")?);
+        std::fs::copy(&*path, &target)
+            .with_context(|| format!("Should be able to save {}", target.display()))?;
+        *path = target;
+    }
+    Ok(())
+}
+
+fn render(report: &Report) -> String {
+    let mut output = String::new();
+    for section in &report.sections {
+        match section {
+            Section::Text { header, text } => {
+                if let Some(header) = header {
+                    writeln!(output, "{header}:").unwrap();
+                }
+                writeln!(output, "{}", text.trim_end()).unwrap();
+            }
+            Section::Code { header, code, .. } => {
+                if let Some(header) = header {
+                    writeln!(output, "{header}:").unwrap();
+                }
+                writeln!(output, "{}", code.trim_end()).unwrap();
+            }
+            Section::Json { header, value } => {
+                let json = serde_json::to_string_pretty(value).unwrap();
+                writeln!(output, "{header}:\n{json}").unwrap();
+            }
+            Section::Table { header, rows } => {
+                if let Some(header) = header {
+                    writeln!(output, "{header}").unwrap();
+                }
+                let width = rows.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+                for (key, value) in rows {
+                    writeln!(output, "  {key:width$}  {value}").unwrap();
+                }
+            }
+            Section::Photo { url, caption } => {
+                writeln!(output, "Photo: {url}").unwrap();
+                if let Some(caption) = caption {
+                    writeln!(output, "{}", caption.trim_end()).unwrap();
+                }
+            }
+            Section::PhotoGroup(photos) => {
+                writeln!(output, "Photos:").unwrap();
+                for photo in photos {
+                    writeln!(output, "- {}", photo.url).unwrap();
+                    if let Some(caption) = &photo.caption {
+                        writeln!(output, "  {}", caption.trim_end()).unwrap();
+                    }
+                }
+            }
+            Section::Audio(audio) => {
+                writeln!(output, "Audio: {}", audio.url).unwrap();
+                if let Some(title) = &audio.title {
+                    writeln!(output, "  title: {title}").unwrap();
+                }
+                if let Some(performer) = &audio.performer {
+                    writeln!(output, "  performer: {performer}").unwrap();
+                }
+                if let Some(duration) = audio.duration {
+                    writeln!(output, "  duration: {duration}s").unwrap();
+                }
+            }
+            Section::AudioFile(path) => writeln!(output, "Audio: {}", path.display()).unwrap(),
+            Section::Video(video) => {
+                writeln!(
+                    output,
+                    "Video: {} ({}x{}, {}s)",
+                    video.path.display(),
+                    video.width,
+                    video.height,
+                    video.duration
+                )
+                .unwrap();
+            }
+            Section::Document {
+                filename,
+                caption,
+                content,
+            } => {
+                writeln!(output, "Document {filename}:").unwrap();
+                if let Some(caption) = caption {
+                    writeln!(output, "{}", caption.trim_end()).unwrap();
+                }
+                writeln!(output, "{}", content.trim_end()).unwrap();
+            }
+            Section::TelegramPost { chat, message_id } => {
+                let chat = chat.trim_start_matches('@');
+                writeln!(output, "Telegram post: https://t.me/{chat}/{message_id}").unwrap();
+            }
+            Section::Error(error) => writeln!(output, "Error: {error}").unwrap(),
+        }
+        output += "\n";
+    }
+    output
+}
+
+#[test]
+fn args_parse() {
+    let args = ["https://example.com", "--media-dir", "out"].map(ToOwned::to_owned);
+    let args = Args::parse(args.into_iter()).unwrap();
+    assert_eq!(args.url, "https://example.com");
+    assert_eq!(args.media_dir, Some(PathBuf::from("out")));
+    assert!(!args.json);
+
+    let args = ["--json", "https://example.com"].map(ToOwned::to_owned);
+    assert!(Args::parse(args.into_iter()).unwrap().json);
+
+    assert!(Args::parse(std::iter::empty()).is_err());
+    assert!(Args::parse(["--media-dir".to_owned()].into_iter()).is_err());
+}
+
+#[test]
+fn render_table_aligned() {
+    let mut report = Report::default();
+    report.push(Section::Table {
+        header: Some("HTTP/1.1 200 OK".to_owned()),
+        rows: vec![
+            ("server".to_owned(), "nginx".to_owned()),
+            ("content-type".to_owned(), "text/html".to_owned()),
+        ],
+    });
+    assert_eq!(
+        render(&report),
+        "HTTP/1.1 200 OK\n  server        nginx\n  content-type  text/html\n\n"
+    );
+}
@@ -0,0 +1,95 @@
+//! Commands the Telegram bot understands
+
+use crate::Parts;
+
+/// Command and description as shown in the Telegram command menu
+pub const COMMANDS: &[(&str, &str)] = &[
+    ("help", "How to use this bot"),
+    ("headers", "<url> Only HTTP status, headers and redirects"),
+    ("video", "<url> Only download the media with yt-dlp"),
+    ("meta", "<url> Only run the page analyzers"),
+    ("raw", "<url> Send the response body as a document"),
+    ("json", "<url> Everything as a JSON document"),
+    (
+        "default",
+        "Which parts are preselected for links in this chat",
+    ),
+];
+
+pub const HELP: &str = "Send me a link and choose what I should find out about it: the redirects and HTTP headers, metadata like OpenGraph, JSON-LD or site specific details, or the video or audio downloaded with yt-dlp.
+The parts preselected for links in this chat can be changed with /default.
+
+Already know what you want? Use a command in front of the link:
+/headers <url> only HTTP status, headers and redirects
+/video <url> only download the media with yt-dlp
+/meta <url> only run the page analyzers
+/raw <url> send the response body as a document
+/json <url> everything as a JSON document";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// `/start` and `/help`
+    Help,
+    /// Plain urls which get a keyboard to choose the parts to inspect
+    Select,
+    /// Command selecting the parts to inspect
+    Inspect(Parts),
+    /// Show the parts which are preselected in this chat
+    Default,
+    /// Everything collected into a [`JsonDocument`](crate::json_document::JsonDocument)
+    Json,
+}
+
+impl Command {
+    /// Parse the command at the start of the text.
+    ///
+    /// Text without a command lets the user [select](Self::Select) the parts. Unknown commands are [`None`].
+    pub fn parse(text: &str) -> Option<Self> {
+        let Some(command) = text.strip_prefix('/') else {
+            return Some(Self::Select);
+        };
+        let command = command.split_whitespace().next().unwrap_or_default();
+        // In groups commands are suffixed with the bot username like /help@quickscrape_bot
+        let command = command
+            .split_once('@')
+            .map_or(command, |(command, _)| command);
+        let only = Parts::NONE;
+        match command {
+            "start" | "help" => Some(Self::Help),
+            "headers" => Some(Self::Inspect(Parts {
+                headers: true,
+                redirects: true,
+                ..only
+            })),
+            "video" => Some(Self::Inspect(Parts {
+                video: true,
+                ..only
+            })),
+            "meta" => Some(Self::Inspect(Parts {
+                metadata: true,
+                ..only
+            })),
+            "raw" => Some(Self::Inspect(Parts { raw: true, ..only })),
+            "json" => Some(Self::Json),
+            "default" => Some(Self::Default),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn parse_works() {
+    assert_eq!(Command::parse("https://example.com"), Some(Command::Select));
+    assert_eq!(Command::parse("/start"), Some(Command::Help));
+    assert_eq!(Command::parse("/help@quickscrape_bot"), Some(Command::Help));
+    assert_eq!(
+        Command::parse("/json https://example.com"),
+        Some(Command::Json)
+    );
+    let Some(Command::Inspect(parts)) = Command::parse("/headers https://example.com") else {
+        panic!("should be inspect");
+    };
+    assert!(parts.headers && parts.redirects);
+    assert!(!parts.video && !parts.metadata && !parts.raw);
+    assert_eq!(Command::parse("/unknown https://example.com"), None);
+}
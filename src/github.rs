@@ -0,0 +1,411 @@
+use std::fmt::Write as _;
+
+use serde_json::Value;
+use ureq::http::HeaderName;
+use ureq::http::header::{ACCEPT, AUTHORIZATION};
+
+use crate::analyzer::{Analyzer, Page};
+use crate::report::Report;
+
+/// Can be replaced with `GITHUB_API`, for example for GitHub Enterprise
+const DEFAULT_API: &str = "https://api.github.com";
+
+/// First path segments which are GitHub pages and not users or organizations
+const RESERVED_OWNERS: &[&str] = &[
+    "about",
+    "collections",
+    "enterprise",
+    "events",
+    "explore",
+    "features",
+    "login",
+    "marketplace",
+    "notifications",
+    "orgs",
+    "pricing",
+    "search",
+    "settings",
+    "sponsors",
+    "topics",
+    "trending",
+];
+
+pub struct GitHub;
+
+impl Analyzer for GitHub {
+    fn name(&self) -> &'static str {
+        "github"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        matches!(page.uri.host(), Some("github.com" | "www.github.com"))
+            && Target::parse(page.uri.path()).is_some()
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        let Some(target) = Target::parse(page.uri.path()) else {
+            return Ok(Report::default());
+        };
+        let api = std::env::var("GITHUB_API").unwrap_or_else(|_| DEFAULT_API.to_owned());
+        let token = std::env::var("GITHUB_TOKEN").ok();
+        fetch(&api, token.as_deref(), &target)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Target<'path> {
+    Repository {
+        owner: &'path str,
+        repo: &'path str,
+    },
+    /// Issues and pull requests share the same API
+    Issue {
+        owner: &'path str,
+        repo: &'path str,
+        number: u64,
+    },
+}
+
+impl<'path> Target<'path> {
+    fn parse(path: &'path str) -> Option<Self> {
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .collect::<Vec<_>>();
+        let (owner, repo) = match segments.as_slice() {
+            [owner, repo, ..] if !RESERVED_OWNERS.contains(owner) => (*owner, *repo),
+            _ => return None,
+        };
+        let repo = repo.strip_suffix(".git").unwrap_or(repo);
+        match segments.as_slice() {
+            [_, _, "issues" | "pull", number, ..] => Some(Self::Issue {
+                owner,
+                repo,
+                number: number.parse().ok()?,
+            }),
+            _ => Some(Self::Repository { owner, repo }),
+        }
+    }
+}
+
+fn fetch(api: &str, token: Option<&str>, target: &Target) -> anyhow::Result<Report> {
+    let authorization = token.map(|token| format!("Bearer {token}"));
+    let mut headers: Vec<(HeaderName, &str)> = vec![(ACCEPT, "application/vnd.github+json")];
+    if let Some(authorization) = &authorization {
+        headers.push((AUTHORIZATION, authorization));
+    }
+    match target {
+        Target::Repository { owner, repo } => {
+            let repository =
+                crate::http::get_json(&format!("{api}/repos/{owner}/{repo}"), &headers)?;
+            // Repositories without releases respond with 404
+            let release = crate::http::get_json(
+                &format!("{api}/repos/{owner}/{repo}/releases/latest"),
+                &headers,
+            )
+            .ok();
+            Ok(repository_report(&repository, release.as_ref()))
+        }
+        Target::Issue {
+            owner,
+            repo,
+            number,
+        } => {
+            let issue = crate::http::get_json(
+                &format!("{api}/repos/{owner}/{repo}/issues/{number}"),
+                &headers,
+            )?;
+            Ok(issue_report(&issue))
+        }
+    }
+}
+
+fn repository_report(repository: &Value, release: Option<&Value>) -> Report {
+    let mut report = Report::default();
+    let field = |key| repository.get(key).and_then(Value::as_str);
+
+    let mut info = String::new();
+    if let Some(name) = field("full_name") {
+        writeln!(info, "{name}").unwrap();
+    }
+    if let Some(description) = field("description") {
+        writeln!(info, "{description}").unwrap();
+    }
+    info += "\n";
+    for key in [
+        "stargazers_count",
+        "forks_count",
+        "subscribers_count",
+        "open_issues_count",
+    ] {
+        if let Some(count) = repository.get(key).and_then(Value::as_u64) {
+            writeln!(info, "{key}: {count}").unwrap();
+        }
+    }
+    if let Some(language) = field("language") {
+        writeln!(info, "language: {language}").unwrap();
+    }
+    if let Some(license) = repository
+        .get("license")
+        .and_then(|license| license.get("spdx_id").or_else(|| license.get("name")))
+        .and_then(Value::as_str)
+    {
+        writeln!(info, "license: {license}").unwrap();
+    }
+    if let Some(topics) = repository
+        .get("topics")
+        .and_then(Value::as_array)
+        .filter(|topics| !topics.is_empty())
+    {
+        let topics = topics.iter().filter_map(Value::as_str).collect::<Vec<_>>();
+        writeln!(info, "topics: {}", topics.join(", ")).unwrap();
+    }
+    if let Some(homepage) = field("homepage").filter(|homepage| !homepage.is_empty()) {
+        writeln!(info, "homepage: {homepage}").unwrap();
+    }
+    for key in ["created_at", "pushed_at"] {
+        if let Some(time) = field(key) {
+            writeln!(info, "{key}: {time}").unwrap();
+        }
+    }
+    if repository.get("archived").and_then(Value::as_bool) == Some(true) {
+        writeln!(info, "archived").unwrap();
+    }
+    if repository.get("fork").and_then(Value::as_bool) == Some(true)
+        && let Some(parent) = repository
+            .get("parent")
+            .and_then(|parent| parent.get("full_name"))
+            .and_then(Value::as_str)
+    {
+        writeln!(info, "fork of: {parent}").unwrap();
+    }
+    report.text(None, info.trim());
+
+    if let Some(release) = release {
+        report.text(Some("latest release"), release_text(release));
+    }
+
+    for key in [
+        "full_name",
+        "description",
+        "stargazers_count",
+        "forks_count",
+        "open_issues_count",
+        "language",
+        "license",
+        "topics",
+        "homepage",
+        "created_at",
+        "pushed_at",
+        "archived",
+    ] {
+        report.insert_data(key, repository.get(key).cloned());
+    }
+    report.insert_data(
+        "latest_release",
+        release.map(|release| {
+            serde_json::json!({
+                "tag_name": release.get("tag_name"),
+                "name": release.get("name"),
+                "published_at": release.get("published_at"),
+            })
+        }),
+    );
+
+    report
+}
+
+fn release_text(release: &Value) -> String {
+    let field = |key| release.get(key).and_then(Value::as_str);
+    let mut text = String::new();
+    if let Some(tag) = field("tag_name") {
+        writeln!(text, "tag: {tag}").unwrap();
+    }
+    if let Some(name) = field("name").filter(|name| !name.is_empty()) {
+        writeln!(text, "name: {name}").unwrap();
+    }
+    if let Some(published) = field("published_at") {
+        writeln!(text, "published_at: {published}").unwrap();
+    }
+    if let Some(url) = field("html_url") {
+        writeln!(text, "{url}").unwrap();
+    }
+    text
+}
+
+fn issue_report(issue: &Value) -> Report {
+    let mut report = Report::default();
+    let field = |key| issue.get(key).and_then(Value::as_str);
+    let pull_request = issue.get("pull_request");
+
+    let mut info = String::new();
+    let kind = if pull_request.is_some() {
+        "Pull request"
+    } else {
+        "Issue"
+    };
+    let number = issue.get("number").and_then(Value::as_u64).unwrap_or(0);
+    writeln!(
+        info,
+        "{kind} #{number}: {}\n",
+        field("title").unwrap_or_default()
+    )
+    .unwrap();
+
+    let merged = pull_request
+        .and_then(|pull_request| pull_request.get("merged_at"))
+        .and_then(Value::as_str);
+    let state = match (field("state"), merged, field("state_reason")) {
+        (_, Some(_), _) => "merged".to_owned(),
+        (Some(state), None, Some(reason)) => format!("{state} ({reason})"),
+        (Some(state), None, None) => state.to_owned(),
+        (None, None, _) => "unknown".to_owned(),
+    };
+    writeln!(info, "state: {state}").unwrap();
+    if issue.get("draft").and_then(Value::as_bool) == Some(true) {
+        writeln!(info, "draft").unwrap();
+    }
+    if let Some(author) = issue
+        .get("user")
+        .and_then(|user| user.get("login"))
+        .and_then(Value::as_str)
+    {
+        writeln!(info, "author: {author}").unwrap();
+    }
+    let labels = issue
+        .get("labels")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|label| label.get("name").and_then(Value::as_str))
+        .collect::<Vec<_>>();
+    if !labels.is_empty() {
+        writeln!(info, "labels: {}", labels.join(", ")).unwrap();
+    }
+    if let Some(comments) = issue.get("comments").and_then(Value::as_u64) {
+        writeln!(info, "comments: {comments}").unwrap();
+    }
+    for key in ["created_at", "closed_at"] {
+        if let Some(time) = field(key) {
+            writeln!(info, "{key}: {time}").unwrap();
+        }
+    }
+    if let Some(merged) = merged {
+        writeln!(info, "merged_at: {merged}").unwrap();
+    }
+    report.text(None, info);
+
+    if let Some(body) = field("body").filter(|body| !body.trim().is_empty()) {
+        report.text(Some("body"), body);
+    }
+
+    report.insert_data("kind", kind);
+    report.insert_data("number", number);
+    report.insert_data("title", field("title"));
+    report.insert_data("state", state);
+    report.insert_data(
+        "author",
+        issue
+            .get("user")
+            .and_then(|user| user.get("login"))
+            .cloned(),
+    );
+    report.insert_data("labels", labels);
+    report.insert_data("comments", issue.get("comments").cloned());
+    report.insert_data("created_at", issue.get("created_at").cloned());
+    report.insert_data("closed_at", issue.get("closed_at").cloned());
+
+    report
+}
+
+#[test]
+fn target_parse_works() {
+    assert_eq!(
+        Target::parse("/EdJoPaTo/quickscrape-tgbot"),
+        Some(Target::Repository {
+            owner: "EdJoPaTo",
+            repo: "quickscrape-tgbot"
+        })
+    );
+    assert_eq!(
+        Target::parse("/EdJoPaTo/quickscrape-tgbot/tree/main/src"),
+        Some(Target::Repository {
+            owner: "EdJoPaTo",
+            repo: "quickscrape-tgbot"
+        })
+    );
+    assert_eq!(
+        Target::parse("/EdJoPaTo/quickscrape-tgbot/pull/42/files"),
+        Some(Target::Issue {
+            owner: "EdJoPaTo",
+            repo: "quickscrape-tgbot",
+            number: 42
+        })
+    );
+    assert_eq!(Target::parse("/EdJoPaTo"), None);
+    assert_eq!(Target::parse("/orgs/rust-lang/repositories"), None);
+}
+
+#[test]
+fn repository_and_pull_request_via_stand_in_api() {
+    use crate::report::Section;
+    use crate::test_server::{Route, TestServer};
+
+    let api = TestServer::new().serve(vec![
+        Route::json(
+            "/repos/rust-lang/rust",
+            r#"{"full_name":"rust-lang/rust","description":"Empowering everyone to build reliable and efficient software.","stargazers_count":100000,"forks_count":13000,"open_issues_count":9000,"language":"Rust","license":{"key":"other","name":"Other","spdx_id":"NOASSERTION"},"topics":["compiler","rust"],"homepage":"https://www.rust-lang.org","created_at":"2010-06-16T20:39:03Z","pushed_at":"2025-01-19T16:53:21Z","archived":false,"fork":false}"#,
+        ),
+        Route::json(
+            "/repos/rust-lang/rust/releases/latest",
+            r#"{"tag_name":"1.84.0","name":"Rust 1.84.0","published_at":"2025-01-09T15:00:00Z","html_url":"https://github.com/rust-lang/rust/releases/tag/1.84.0"}"#,
+        ),
+        Route::json(
+            "/repos/rust-lang/rust/issues/42",
+            r#"{"number":42,"title":"Make things faster","state":"closed","state_reason":null,"user":{"login":"ferris"},"labels":[{"name":"T-compiler"},{"name":"perf"}],"comments":7,"created_at":"2025-01-01T00:00:00Z","closed_at":"2025-01-02T00:00:00Z","pull_request":{"url":"…","merged_at":"2025-01-02T00:00:00Z"},"body":"Speeds up everything."}"#,
+        ),
+    ]);
+
+    let report = fetch(
+        &api,
+        Some("token"),
+        &Target::Repository {
+            owner: "rust-lang",
+            repo: "rust",
+        },
+    )
+    .unwrap();
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: None, text }
+            if text.starts_with("rust-lang/rust\nEmpowering")
+                && text.contains("stargazers_count: 100000")
+                && text.contains("language: Rust")
+                && text.contains("license: NOASSERTION")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text }
+            if header == "latest release" && text.starts_with("tag: 1.84.0\n")
+    )));
+
+    let report = fetch(
+        &api,
+        None,
+        &Target::Issue {
+            owner: "rust-lang",
+            repo: "rust",
+            number: 42,
+        },
+    )
+    .unwrap();
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: None, text }
+            if text.starts_with("Pull request #42: Make things faster\n")
+                && text.contains("state: merged")
+                && text.contains("author: ferris")
+                && text.contains("labels: T-compiler, perf")
+                && text.contains("comments: 7")
+    )));
+}
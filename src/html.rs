@@ -0,0 +1,26 @@
+//! Helpers for HTML fragments
+
+use lazy_regex::regex_replace_all;
+use scraper::Html;
+
+/// Plain text of HTML content keeping line and paragraph breaks
+pub fn html_to_text(html: &str) -> String {
+    let html = regex_replace_all!(r"(?i)<br\s*/?>", html, "\n");
+    let html = regex_replace_all!(r"(?i)</p>", &html, "\n\n");
+    Html::parse_fragment(&html)
+        .root_element()
+        .text()
+        .collect::<String>()
+        .trim()
+        .to_owned()
+}
+
+#[test]
+fn html_to_text_works() {
+    assert_eq!(
+        html_to_text(
+            r#"<p>Hello <a href="https://example.com/@user" class="u-url mention">@<span>user</span></a><br/>second line</p><p>Tom &amp; Jerry</p>"#
+        ),
+        "Hello @user\nsecond line\n\nTom & Jerry"
+    );
+}
@@ -1,18 +1,98 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use serde_json::Value;
 use ureq::config::Config;
 use ureq::http::header::USER_AGENT;
-use ureq::http::{HeaderValue, Response};
-use ureq::{Agent, Body};
+use ureq::http::{HeaderName, HeaderValue, Response, Uri};
+use ureq::{Agent, Body, ResponseExt as _};
+
+/// Inline queries are answered while the user types, slow servers should not delay that
+const RESOLVE_TIMEOUT: Duration = Duration::from_secs(3);
+const RESOLVE_MAX_REDIRECTS: u32 = 5;
 
 /// Firefox ESR
 const USER_AGENT_VALUE: HeaderValue = HeaderValue::from_static(
     "Mozilla/5.0 (X11; Fedora; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0",
 );
 
-pub fn get(url: &str) -> Result<Response<Body>, ureq::Error> {
+fn agent() -> Agent {
     let config = Config::builder()
         .http_status_as_error(false)
         .save_redirect_history(true)
         .build();
-    let agent = Agent::new_with_config(config);
-    agent.get(url).header(USER_AGENT, USER_AGENT_VALUE).call()
+    Agent::new_with_config(config)
+}
+
+pub fn get(url: &str) -> Result<Response<Body>, ureq::Error> {
+    agent().get(url).header(USER_AGENT, USER_AGENT_VALUE).call()
+}
+
+/// The uri the url finally redirects to. Gives up on slow servers and long redirect chains.
+pub fn final_uri(url: &str) -> Result<Uri, ureq::Error> {
+    final_uri_within(url, RESOLVE_TIMEOUT)
+}
+
+fn final_uri_within(url: &str, timeout: Duration) -> Result<Uri, ureq::Error> {
+    let config = Config::builder()
+        .http_status_as_error(false)
+        .timeout_global(Some(timeout))
+        .max_redirects(RESOLVE_MAX_REDIRECTS)
+        .build();
+    let response = Agent::new_with_config(config)
+        .get(url)
+        .header(USER_AGENT, USER_AGENT_VALUE)
+        .call()?;
+    Ok(response.get_uri().clone())
+}
+
+/// GET the url with additional headers and parse the body as JSON.
+///
+/// Non successful status codes are an error.
+pub fn get_json(url: &str, headers: &[(HeaderName, &str)]) -> anyhow::Result<Value> {
+    let mut request = agent().get(url).header(USER_AGENT, USER_AGENT_VALUE);
+    for (key, value) in headers {
+        request = request.header(key, *value);
+    }
+    let mut response = request.call().context("HTTP GET request failed")?;
+    let status = response.status();
+    anyhow::ensure!(status.is_success(), "HTTP GET {url} returned {status}");
+    let body = response
+        .body_mut()
+        .read_to_string()
+        .context("Body should be a string")?;
+    serde_json::from_str(&body).context("Body should be JSON")
+}
+
+/// The uri without its query which is often only used for tracking
+pub fn without_query(uri: &Uri) -> Option<String> {
+    let query = uri.query()?;
+    Some(uri.to_string().replace(&format!("?{query}"), ""))
+}
+
+#[test]
+fn without_query_works() {
+    let uri = Uri::from_static("https://example.com/path?utm_source=share");
+    assert_eq!(
+        without_query(&uri).as_deref(),
+        Some("https://example.com/path")
+    );
+    assert_eq!(
+        without_query(&Uri::from_static("https://example.com/path")),
+        None
+    );
+}
+
+#[test]
+fn final_uri_gives_up_on_silent_servers() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    // Accept the connection but never answer
+    std::thread::spawn(move || {
+        let connections = listener.incoming().collect::<Vec<_>>();
+        drop(connections);
+    });
+    let start = std::time::Instant::now();
+    assert!(final_uri_within(&url, Duration::from_millis(200)).is_err());
+    assert!(start.elapsed() < Duration::from_secs(2));
 }
@@ -0,0 +1,132 @@
+//! Results for inline queries like `@bot <url>` which can be sent into any chat
+
+use frankenstein::inline_mode::{
+    InlineQueryResult, InlineQueryResultArticle, InlineQueryResultCachedVideo, InputMessageContent,
+    InputTextMessageContent,
+};
+use ureq::http::Uri;
+
+use crate::http::without_query;
+
+/// The url at the start of the query. The scheme may be omitted.
+pub fn parse_url(query: &str) -> Option<Uri> {
+    let query = query.split_whitespace().next()?;
+    let uri = if query.starts_with("http://") || query.starts_with("https://") {
+        query.parse::<Uri>()
+    } else {
+        format!("https://{query}").parse::<Uri>()
+    }
+    .ok()?;
+    uri.host().filter(|host| host.contains('.'))?;
+    Some(uri)
+}
+
+/// Cleaned up variants of the url and the already sent video when there is one
+pub fn results(
+    uri: &Uri,
+    final_uri: Option<&Uri>,
+    video_file_id: Option<&str>,
+) -> Vec<InlineQueryResult> {
+    let mut links = Vec::new();
+    if let Some(cleaned) = without_query(uri) {
+        links.push(("Without query", cleaned));
+    }
+    if let Some(final_uri) = final_uri.filter(|final_uri| *final_uri != uri) {
+        links.push(("Final target", final_uri.to_string()));
+        if let Some(cleaned) = without_query(final_uri) {
+            links.push(("Final target without query", cleaned));
+        }
+    }
+    if links.is_empty() {
+        links.push(("Url", uri.to_string()));
+    }
+    let mut seen = Vec::new();
+    links.retain(|(_, link)| {
+        let new = !seen.contains(link);
+        seen.push(link.clone());
+        new
+    });
+
+    let mut results = links
+        .into_iter()
+        .enumerate()
+        .map(|(index, (title, link))| {
+            InlineQueryResultArticle::builder()
+                .id(format!("link{index}"))
+                .title(title)
+                .description(link.clone())
+                .input_message_content(InputMessageContent::Text(
+                    InputTextMessageContent::builder()
+                        .message_text(link)
+                        .build(),
+                ))
+                .build()
+                .into()
+        })
+        .collect::<Vec<InlineQueryResult>>();
+
+    if let Some(file_id) = video_file_id {
+        results.insert(
+            0,
+            InlineQueryResultCachedVideo::builder()
+                .id("video")
+                .video_file_id(file_id)
+                .title("Video")
+                .build()
+                .into(),
+        );
+    }
+    results
+}
+
+#[test]
+fn parse_url_works() {
+    assert_eq!(
+        parse_url("https://example.com/a?b=c"),
+        Some(Uri::from_static("https://example.com/a?b=c"))
+    );
+    assert_eq!(
+        parse_url("  example.com/a more text"),
+        Some(Uri::from_static("https://example.com/a"))
+    );
+    assert_eq!(parse_url("hello"), None);
+    assert_eq!(parse_url(""), None);
+}
+
+#[test]
+fn results_are_deduplicated() {
+    let uri = Uri::from_static("https://short.link/abc?utm=1");
+    let final_uri = Uri::from_static("https://example.com/article?utm=1");
+    let answers = results(&uri, Some(&final_uri), Some("VIDEO_FILE_ID"));
+    let titles = answers
+        .iter()
+        .map(|result| match result {
+            InlineQueryResult::Article(article) => article.title.as_str(),
+            InlineQueryResult::Video(_) => "video",
+            _ => "unexpected",
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        titles,
+        [
+            "video",
+            "Without query",
+            "Final target",
+            "Final target without query"
+        ]
+    );
+
+    let uri = Uri::from_static("https://example.com/");
+    assert_eq!(results(&uri, Some(&uri), None).len(), 1);
+}
+
+#[test]
+fn unresolved_is_without_query_only() {
+    let uri = Uri::from_static("https://short.link/abc?utm=1");
+    let answers = results(&uri, None, None);
+    assert_eq!(answers.len(), 1);
+    assert!(matches!(
+        &answers[0],
+        InlineQueryResult::Article(article) if article.title == "Without query"
+    ));
+}
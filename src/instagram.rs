@@ -0,0 +1,193 @@
+use std::fmt::Write as _;
+
+use anyhow::Context as _;
+use scraper::Html;
+use serde_json::Value;
+
+use crate::analyzer::{Analyzer, Page, host_is};
+use crate::json::{deep_get, find_key, parse_timestamp};
+use crate::macros::selector;
+use crate::report::{Photo, Report};
+use crate::single::Single as _;
+
+pub struct Instagram;
+
+impl Analyzer for Instagram {
+    fn name(&self) -> &'static str {
+        "instagram"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        let uri = page.uri;
+        host_is(uri, "instagram.com")
+            && ["/p/", "/reel/", "/reels/", "/tv/"]
+                .iter()
+                .any(|prefix| uri.path().starts_with(prefix))
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        analyze(page.body)
+    }
+}
+
+fn analyze(body: &str) -> anyhow::Result<Report> {
+    let item = extract_json(body)?;
+    let mut report = Report::default();
+
+    let caption = deep_get(&item, ["caption", "text"]).and_then(Value::as_str);
+    if let Some(caption) = caption {
+        report.text(Some("caption"), caption);
+    }
+
+    let mut info = String::new();
+    if let Some(user) = item.get("user") {
+        let username = user.get("username").and_then(Value::as_str);
+        let full_name = user.get("full_name").and_then(Value::as_str);
+        match (username, full_name) {
+            (Some(username), Some(full_name)) if !full_name.is_empty() => {
+                writeln!(info, "author: @{username} ({full_name})").unwrap();
+            }
+            (Some(username), _) => writeln!(info, "author: @{username}").unwrap(),
+            _ => {}
+        }
+    }
+    if let Some(time) = item.get("taken_at").and_then(parse_timestamp) {
+        writeln!(info, "taken_at: {time}").unwrap();
+    }
+    if let Some(time) = deep_get(&item, ["caption", "created_at"]).and_then(parse_timestamp) {
+        writeln!(info, "caption created_at: {time}").unwrap();
+    }
+    for key in ["like_count", "comment_count", "play_count", "view_count"] {
+        if let Some(count) = item.get(key).and_then(Value::as_u64) {
+            writeln!(info, "{key}: {count}").unwrap();
+        }
+    }
+    if let Some(location) = item.get("location").filter(|location| !location.is_null()) {
+        let name = location.get("name").and_then(Value::as_str).unwrap_or("");
+        let lat = location.get("lat").and_then(Value::as_f64);
+        let lng = location.get("lng").and_then(Value::as_f64);
+        if let (Some(lat), Some(lng)) = (lat, lng) {
+            writeln!(info, "location: {name} ({lat}, {lng})").unwrap();
+        } else {
+            writeln!(info, "location: {name}").unwrap();
+        }
+    }
+    if !info.is_empty() {
+        report.text(None, info);
+    }
+
+    let media = item
+        .get("carousel_media")
+        .and_then(Value::as_array)
+        .map_or_else(|| vec![&item], |carousel| carousel.iter().collect());
+    let images = media
+        .iter()
+        .filter_map(|media| image_url(media))
+        .collect::<Vec<_>>();
+    let videos = media
+        .iter()
+        .filter_map(|media| video_url(media))
+        .collect::<Vec<_>>();
+
+    let mut urls = String::new();
+    for url in &images {
+        writeln!(urls, "- {url}").unwrap();
+    }
+    for url in &videos {
+        writeln!(urls, "- {url}").unwrap();
+    }
+    if !urls.is_empty() {
+        report.text(Some("media"), urls);
+    }
+
+    report.photos(
+        images
+            .iter()
+            .map(|url| Photo {
+                url: (*url).to_owned(),
+                caption: None,
+            })
+            .collect(),
+    );
+
+    report.insert_data("code", item.get("code").cloned());
+    report.insert_data("caption", caption);
+    report.insert_data("user", item.get("user").cloned());
+    report.insert_data("taken_at", item.get("taken_at").cloned());
+    report.insert_data("like_count", item.get("like_count").cloned());
+    report.insert_data("comment_count", item.get("comment_count").cloned());
+    report.insert_data("location", item.get("location").cloned());
+    report.insert_data("images", images);
+    report.insert_data("videos", videos);
+
+    Ok(report)
+}
+
+/// Largest image candidate
+fn image_url(media: &Value) -> Option<&str> {
+    deep_get(media, ["image_versions2", "candidates"])
+        .and_then(Value::as_array)?
+        .iter()
+        .max_by_key(|candidate| candidate.get("width").and_then(Value::as_u64))?
+        .get("url")?
+        .as_str()
+}
+
+fn video_url(media: &Value) -> Option<&str> {
+    media
+        .get("video_versions")
+        .and_then(Value::as_array)?
+        .first()?
+        .get("url")?
+        .as_str()
+}
+
+fn extract_json(html: &str) -> anyhow::Result<Value> {
+    Html::parse_document(html)
+        .select(selector!(r#"body script[type="application/json"]"#))
+        .filter_map(|element| serde_json::from_str::<Value>(element.inner_html().trim()).ok())
+        .filter_map(|value| {
+            find_key(&value, "xdt_api__v1__media__shortcode__web_info")
+                .and_then(|info| info.get("items"))
+                .and_then(Value::as_array)
+                .and_then(|items| items.first())
+                .cloned()
+        })
+        .single()
+        .context("Should have a single relevant json script in body")
+}
+
+#[test]
+fn dhq_report() {
+    use crate::report::Section;
+
+    let html = include_str!("../test/instagram-DHq3sXcNw2L.html");
+    let report = analyze(html).unwrap();
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text }
+            if header == "caption" && text.starts_with("Sunday hike")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: None, text }
+            if text.contains("author: @harz.hiker (Harz Hiker)")
+                && text.contains("taken_at: 2025-01-19 16:53:21 UTC")
+                && text.contains("like_count: 1234")
+                && text.contains("location: Brocken (51.7991, 10.6156)")
+    )));
+    let group = report
+        .sections
+        .iter()
+        .find_map(|section| match section {
+            Section::PhotoGroup(photos) => Some(photos),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(group.len(), 3);
+    assert!(group[0].url.contains("p1440x1440"));
+    assert_eq!(
+        report.data["videos"],
+        serde_json::json!(["https://scontent.cdninstagram.com/o1/v/t16/f2/2_video.mp4"])
+    );
+}
@@ -0,0 +1,85 @@
+//! Helpers for the JSON embedded in pages or returned by APIs
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+/// Follow the keys into nested objects
+pub fn deep_get<'json, const N: usize>(
+    mut json: &'json Value,
+    keys: [&'static str; N],
+) -> Option<&'json Value> {
+    for key in keys {
+        json = json.get(key)?;
+    }
+    Some(json)
+}
+
+/// Unix timestamp in seconds as number or string. 0 is treated as not set.
+#[expect(clippy::cast_possible_truncation)]
+pub fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
+    let unix_timestamp = match value {
+        Value::Number(number) => number
+            .as_i64()
+            // Some APIs like reddit use floats for seconds
+            .or_else(|| number.as_f64().map(|float| float as i64)),
+        Value::String(str) => str.parse().ok(),
+        _ => None,
+    }?;
+    if unix_timestamp == 0 {
+        return None;
+    }
+    DateTime::from_timestamp(unix_timestamp, 0)
+}
+
+/// Positive number which might also be given as string like `"754"`
+pub fn parse_number(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(str) => str.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Depth first search for the first value with the given key
+pub fn find_key<'json>(json: &'json Value, key: &str) -> Option<&'json Value> {
+    match json {
+        Value::Object(object) => object
+            .get(key)
+            .or_else(|| object.values().find_map(|value| find_key(value, key))),
+        Value::Array(array) => array.iter().find_map(|value| find_key(value, key)),
+        _ => None,
+    }
+}
+
+#[test]
+fn parse_timestamp_works() {
+    let expected = chrono::NaiveDate::from_ymd_opt(2025, 1, 19)
+        .unwrap()
+        .and_hms_opt(16, 53, 21)
+        .unwrap()
+        .and_utc();
+    assert_eq!(parse_timestamp(&0.into()), None);
+    assert_eq!(parse_timestamp(&Value::String("0".to_owned())), None);
+    assert_eq!(parse_timestamp(&1_737_305_601.into()), Some(expected));
+    assert_eq!(parse_timestamp(&1_737_305_601.0.into()), Some(expected));
+    assert_eq!(
+        parse_timestamp(&Value::String("1737305601".to_owned())),
+        Some(expected)
+    );
+}
+
+#[test]
+fn parse_number_works() {
+    assert_eq!(parse_number(&754.into()), Some(754));
+    assert_eq!(parse_number(&Value::String("754".to_owned())), Some(754));
+    assert_eq!(parse_number(&Value::String("nope".to_owned())), None);
+    assert_eq!(parse_number(&Value::Null), None);
+}
+
+#[test]
+fn find_key_works() {
+    let json = serde_json::json!({"a": [1, {"b": {"needle": 42}}], "needle": 1});
+    assert_eq!(find_key(&json, "needle"), Some(&1.into()));
+    assert_eq!(find_key(&json["a"], "needle"), Some(&42.into()));
+    assert_eq!(find_key(&json, "missing"), None);
+}
@@ -0,0 +1,74 @@
+//! Machine readable form of all the [`Report`]s of a single inspection.
+//!
+//! The structure is versioned via [`VERSION`] which is increased on breaking changes.
+
+use serde_json::{Map, Value};
+
+use crate::report::{Report, Section};
+
+pub const VERSION: u32 = 1;
+
+pub struct JsonDocument {
+    url: String,
+    stages: Map<String, Value>,
+}
+
+impl JsonDocument {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_owned(),
+            stages: Map::new(),
+        }
+    }
+
+    /// Add the data of a report emitted by the given stage of [`crate::inspect_url`]
+    pub fn add(&mut self, stage: &str, report: &Report) {
+        let mut data = report.data.clone();
+        let errors = report
+            .sections
+            .iter()
+            .filter_map(|section| match section {
+                Section::Error(error) => Some(Value::from(error.as_str())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            data.insert("errors".to_owned(), Value::Array(errors));
+        }
+        self.stages.insert(stage.to_owned(), Value::Object(data));
+    }
+
+    pub fn to_value(&self) -> Value {
+        serde_json::json!({
+            "version": VERSION,
+            "url": self.url,
+            "stages": self.stages,
+        })
+    }
+
+    pub fn to_string_pretty(&self) -> String {
+        serde_json::to_string_pretty(&self.to_value()).expect("Should be able to serialize json")
+    }
+}
+
+#[test]
+fn document_contains_stages_and_errors() {
+    let mut report = Report::default();
+    report.insert_data("status", 200);
+    report.error("something broke");
+    let mut document = JsonDocument::new("https://example.com");
+    document.add("http", &report);
+    assert_eq!(
+        document.to_value(),
+        serde_json::json!({
+            "version": 1,
+            "url": "https://example.com",
+            "stages": {
+                "http": {
+                    "status": 200,
+                    "errors": ["something broke"],
+                },
+            },
+        })
+    );
+}
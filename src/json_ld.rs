@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use scraper::{ElementRef, Html};
+use serde_json::{Map, Value};
+
+use crate::analyzer::{Analyzer, Page};
+use crate::macros::selector;
+use crate::report::{Report, Section};
+
+/// Structured data embedded as `<script type="application/ld+json">` or microdata
+pub struct JsonLd;
+
+impl Analyzer for JsonLd {
+    fn name(&self) -> &'static str {
+        "json-ld"
+    }
+
+    fn matches(&self, _page: &Page) -> bool {
+        true
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        Ok(analyze(page.body))
+    }
+}
+
+fn analyze(body: &str) -> Report {
+    let html = Html::parse_document(body);
+    let mut report = Report::default();
+
+    let groups = group_by_type(extract_json_ld(&html, &mut report));
+    for (kind, items) in &groups {
+        for item in items {
+            if let Some(summary) = summarize(kind, item) {
+                report.text(Some(kind), summary);
+            }
+        }
+        let value = match items.as_slice() {
+            [single] => single.clone(),
+            _ => Value::Array(items.clone()),
+        };
+        report.push(Section::Json {
+            header: format!("JSON-LD {kind}"),
+            value,
+        });
+    }
+    report.insert_data(
+        "json_ld",
+        groups
+            .into_iter()
+            .map(|(kind, items)| (kind, Value::Array(items)))
+            .collect::<Map<_, _>>(),
+    );
+
+    let microdata = extract_microdata(&html);
+    for item in &microdata {
+        report.push(Section::Json {
+            header: "Microdata".to_owned(),
+            value: item.clone(),
+        });
+    }
+    report.insert_data("microdata", microdata);
+
+    report
+}
+
+/// All JSON-LD objects of the page. Arrays and `@graph` are flattened.
+fn extract_json_ld(html: &Html, report: &mut Report) -> Vec<Value> {
+    let mut items = Vec::new();
+    for element in html.select(selector!(r#"script[type="application/ld+json"]"#)) {
+        match serde_json::from_str::<Value>(element.inner_html().trim()) {
+            Ok(value) => flatten(value, &mut items),
+            Err(error) => report.error(format!("Failed to parse JSON-LD: {error}")),
+        }
+    }
+    items
+}
+
+fn flatten(value: Value, items: &mut Vec<Value>) {
+    match value {
+        Value::Array(array) => {
+            for value in array {
+                flatten(value, items);
+            }
+        }
+        Value::Object(mut object) => {
+            if let Some(graph) = object.remove("@graph") {
+                flatten(graph, items);
+            }
+            if object.keys().any(|key| key != "@context") {
+                items.push(Value::Object(object));
+            }
+        }
+        _ => {}
+    }
+}
+
+fn group_by_type(items: Vec<Value>) -> BTreeMap<String, Vec<Value>> {
+    let mut groups = BTreeMap::<String, Vec<Value>>::new();
+    for item in items {
+        let kind = match item.get("@type") {
+            Some(Value::String(kind)) => kind.clone(),
+            Some(Value::Array(kinds)) => kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(", "),
+            _ => "unknown".to_owned(),
+        };
+        groups.entry(kind).or_default().push(item);
+    }
+    groups
+}
+
+/// Short human readable summary for common types
+fn summarize(kind: &str, item: &Value) -> Option<String> {
+    let fields: &[(&str, &[&str])] = match kind {
+        "Article" | "NewsArticle" | "BlogPosting" | "ReportageNewsArticle" => &[
+            ("headline", &["headline"]),
+            ("author", &["author"]),
+            ("published", &["datePublished"]),
+            ("modified", &["dateModified"]),
+            ("publisher", &["publisher"]),
+        ],
+        "Product" => &[
+            ("name", &["name"]),
+            ("brand", &["brand"]),
+            ("price", &["offers", "price"]),
+            ("currency", &["offers", "priceCurrency"]),
+            ("availability", &["offers", "availability"]),
+            ("rating", &["aggregateRating", "ratingValue"]),
+            ("reviews", &["aggregateRating", "reviewCount"]),
+        ],
+        "VideoObject" => &[
+            ("name", &["name"]),
+            ("duration", &["duration"]),
+            ("uploaded", &["uploadDate"]),
+            ("content", &["contentUrl"]),
+            ("embed", &["embedUrl"]),
+        ],
+        _ => return None,
+    };
+    let mut summary = String::new();
+    for (label, keys) in fields {
+        let mut value = Some(item);
+        for key in *keys {
+            value = value.and_then(|value| lookup(value, key));
+        }
+        if let Some(text) = value.and_then(display_value) {
+            writeln!(summary, "{label}: {text}").unwrap();
+        }
+    }
+    Some(summary).filter(|summary| !summary.is_empty())
+}
+
+/// Get the key of an object or the first object of an array having it
+fn lookup<'value>(value: &'value Value, key: &str) -> Option<&'value Value> {
+    match value {
+        Value::Array(array) => array.iter().find_map(|value| value.get(key)),
+        _ => value.get(key),
+    }
+}
+
+/// Strings and numbers as they are, things like authors by their name
+fn display_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Object(object) => object.get("name").and_then(display_value),
+        Value::Array(array) => {
+            let values = array.iter().filter_map(display_value).collect::<Vec<_>>();
+            Some(values.join(", ")).filter(|joined| !joined.is_empty())
+        }
+        Value::Null | Value::Bool(_) => None,
+    }
+}
+
+/// Top level `itemscope` elements with their `itemprop`s
+fn extract_microdata(html: &Html) -> Vec<Value> {
+    html.select(selector!("[itemscope]"))
+        .filter(|element| {
+            !element
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(|ancestor| ancestor.attr("itemscope").is_some())
+        })
+        .map(microdata_item)
+        .collect()
+}
+
+fn microdata_item(scope: ElementRef) -> Value {
+    let mut item = Map::new();
+    if let Some(kind) = scope.attr("itemtype") {
+        item.insert("@type".to_owned(), kind.into());
+    }
+    for element in scope.select(selector!("[itemprop]")) {
+        // Only properties belonging directly to this scope
+        let owner = element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .find(|ancestor| ancestor.attr("itemscope").is_some());
+        if owner != Some(scope) {
+            continue;
+        }
+        let Some(name) = element.attr("itemprop") else {
+            continue;
+        };
+        let value = if element.attr("itemscope").is_some() {
+            microdata_item(element)
+        } else {
+            element
+                .attr("content")
+                .or_else(|| element.attr("href"))
+                .or_else(|| element.attr("src"))
+                .or_else(|| element.attr("datetime"))
+                .map_or_else(
+                    || element.text().collect::<String>().trim().to_owned(),
+                    ToOwned::to_owned,
+                )
+                .into()
+        };
+        item.insert(name.to_owned(), value);
+    }
+    Value::Object(item)
+}
+
+#[test]
+fn json_ld_grouped_and_summarized() {
+    let html = r#"<html><head>
+        <script type="application/ld+json">{"@context":"https://schema.org","@graph":[
+            {"@type":"NewsArticle","headline":"Something happened","author":[{"@type":"Person","name":"Jane"}],"datePublished":"2025-01-19"},
+            {"@type":"BreadcrumbList","itemListElement":[]}
+        ]}</script>
+        <script type="application/ld+json">[{"@context":"https://schema.org","@type":"Product","name":"Thing","offers":{"price":"9.99","priceCurrency":"EUR"}}]</script>
+        <script type="application/ld+json">{ broken</script>
+    </head></html>"#;
+    let report = analyze(html);
+    let json_headers = report
+        .sections
+        .iter()
+        .filter_map(|section| match section {
+            Section::Json { header, .. } => Some(header.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        json_headers,
+        [
+            "JSON-LD BreadcrumbList",
+            "JSON-LD NewsArticle",
+            "JSON-LD Product"
+        ]
+    );
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { text, .. }
+            if text == "headline: Something happened\nauthor: Jane\npublished: 2025-01-19\n"
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { text, .. } if text == "name: Thing\nprice: 9.99\ncurrency: EUR\n"
+    )));
+    assert!(
+        report
+            .sections
+            .iter()
+            .any(|section| matches!(section, Section::Error(_)))
+    );
+}
+
+#[test]
+fn microdata_works() {
+    let html = r#"<div itemscope itemtype="https://schema.org/Recipe">
+        <h1 itemprop="name">Pancakes</h1>
+        <meta itemprop="totalTime" content="PT20M">
+        <div itemprop="author" itemscope itemtype="https://schema.org/Person">
+            <span itemprop="name">Jane</span>
+        </div>
+    </div>"#;
+    let microdata = extract_microdata(&Html::parse_document(html));
+    assert_eq!(
+        microdata,
+        [serde_json::json!({
+            "@type": "https://schema.org/Recipe",
+            "name": "Pancakes",
+            "totalTime": "PT20M",
+            "author": {
+                "@type": "https://schema.org/Person",
+                "name": "Jane",
+            },
+        })]
+    );
+}
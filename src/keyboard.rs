@@ -0,0 +1,233 @@
+//! Inline keyboard to choose the [`Parts`] to inspect.
+//!
+//! The keyboard is stateless: every button carries the complete selection in its callback data.
+
+use frankenstein::types::{InlineKeyboardButton, InlineKeyboardMarkup};
+
+use crate::Parts;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    Headers,
+    Redirects,
+    Metadata,
+    Video,
+    Audio,
+}
+
+impl Part {
+    const ALL: [Self; 5] = [
+        Self::Headers,
+        Self::Redirects,
+        Self::Metadata,
+        Self::Video,
+        Self::Audio,
+    ];
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Headers => "Headers",
+            Self::Redirects => "Redirects",
+            Self::Metadata => "Metadata",
+            Self::Video => "Video",
+            Self::Audio => "Audio",
+        }
+    }
+
+    /// Short representation for the callback data
+    const fn letter(self) -> char {
+        match self {
+            Self::Headers => 'h',
+            Self::Redirects => 'r',
+            Self::Metadata => 'm',
+            Self::Video => 'v',
+            Self::Audio => 'a',
+        }
+    }
+
+    const fn flag(self, parts: &mut Parts) -> &mut bool {
+        match self {
+            Self::Headers => &mut parts.headers,
+            Self::Redirects => &mut parts.redirects,
+            Self::Metadata => &mut parts.metadata,
+            Self::Video => &mut parts.video,
+            Self::Audio => &mut parts.audio,
+        }
+    }
+
+    const fn is_in(self, mut parts: Parts) -> bool {
+        *self.flag(&mut parts)
+    }
+}
+
+/// What a keyboard is shown for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Purpose {
+    /// Choose the parts to inspect for the links in the replied to message
+    Inspect,
+    /// Choose the parts preselected in the chat
+    Default,
+}
+
+impl Purpose {
+    /// Short representation for the callback data
+    const fn letter(self) -> &'static str {
+        match self {
+            Self::Inspect => "i",
+            Self::Default => "d",
+        }
+    }
+
+    fn parse(letter: &str) -> Option<Self> {
+        match letter {
+            "i" => Some(Self::Inspect),
+            "d" => Some(Self::Default),
+            _ => None,
+        }
+    }
+}
+
+/// Result of pressing a button
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Show the keyboard with the toggled selection
+    Show(Purpose, Parts),
+    Run(Parts),
+    /// Save the selection as default of the chat from the keyboard shown for the purpose
+    SaveDefault(Purpose, Parts),
+}
+
+impl Action {
+    pub fn parse(data: &str) -> Option<Self> {
+        let (action, letters) = data.split_once(':')?;
+        if action == "save" {
+            let (purpose, letters) = letters.split_once(':')?;
+            return Some(Self::SaveDefault(
+                Purpose::parse(purpose)?,
+                decode(letters)?,
+            ));
+        }
+        let parts = decode(letters)?;
+        match action {
+            "run" => Some(Self::Run(parts)),
+            _ => Some(Self::Show(Purpose::parse(action)?, parts)),
+        }
+    }
+}
+
+pub fn markup(purpose: Purpose, parts: Parts) -> InlineKeyboardMarkup {
+    let show = purpose.letter();
+    let toggles = Part::ALL
+        .iter()
+        .map(|part| {
+            let mut toggled = parts;
+            let flag = part.flag(&mut toggled);
+            *flag = !*flag;
+            let text = if part.is_in(parts) {
+                format!("✅ {}", part.label())
+            } else {
+                part.label().to_owned()
+            };
+            button(&text, &format!("{show}:{}", encode(toggled)))
+        })
+        .collect::<Vec<_>>();
+    let mut keyboard = toggles.chunks(3).map(<[_]>::to_vec).collect::<Vec<_>>();
+    let save = button("Save as default", &format!("save:{show}:{}", encode(parts)));
+    match purpose {
+        Purpose::Inspect => {
+            keyboard.push(vec![
+                button("Everything", &format!("run:{}", encode(Parts::EVERYTHING))),
+                button("Run", &format!("run:{}", encode(parts))),
+            ]);
+            keyboard.push(vec![save]);
+        }
+        Purpose::Default => keyboard.push(vec![save]),
+    }
+    InlineKeyboardMarkup::builder()
+        .inline_keyboard(keyboard)
+        .build()
+}
+
+/// Human readable list of the selected parts
+pub fn describe(parts: Parts) -> String {
+    let labels = Part::ALL
+        .iter()
+        .filter(|part| part.is_in(parts))
+        .map(|part| part.label())
+        .collect::<Vec<_>>();
+    if labels.is_empty() {
+        "nothing".to_owned()
+    } else {
+        labels.join(", ")
+    }
+}
+
+fn button(text: &str, callback_data: &str) -> InlineKeyboardButton {
+    InlineKeyboardButton::builder()
+        .text(text)
+        .callback_data(callback_data)
+        .build()
+}
+
+fn encode(parts: Parts) -> String {
+    Part::ALL
+        .iter()
+        .filter(|part| part.is_in(parts))
+        .map(|part| part.letter())
+        .collect()
+}
+
+fn decode(letters: &str) -> Option<Parts> {
+    let mut parts = Parts::NONE;
+    for letter in letters.chars() {
+        let part = Part::ALL.into_iter().find(|part| part.letter() == letter)?;
+        *part.flag(&mut parts) = true;
+    }
+    Some(parts)
+}
+
+#[test]
+fn encode_decode_roundtrip() {
+    for parts in [Parts::NONE, Parts::DEFAULT, Parts::EVERYTHING] {
+        assert_eq!(decode(&encode(parts)), Some(parts));
+    }
+    assert_eq!(encode(Parts::DEFAULT), "hrmv");
+    assert_eq!(decode("x"), None);
+}
+
+#[test]
+fn buttons_toggle_their_part() {
+    let markup = markup(Purpose::Inspect, Parts::DEFAULT);
+    let headers = &markup.inline_keyboard[0][0];
+    assert_eq!(headers.text, "✅ Headers");
+    let Some(Action::Show(Purpose::Inspect, toggled)) =
+        Action::parse(headers.callback_data.as_deref().unwrap())
+    else {
+        panic!("headers should toggle");
+    };
+    assert_eq!(
+        toggled,
+        Parts {
+            headers: false,
+            ..Parts::DEFAULT
+        }
+    );
+    assert_eq!(markup.inline_keyboard[1][1].text, "Audio");
+    assert_eq!(
+        Action::parse("run:hrmva"),
+        Some(Action::Run(Parts::EVERYTHING))
+    );
+
+    let save = &markup.inline_keyboard[3][0];
+    assert_eq!(
+        Action::parse(save.callback_data.as_deref().unwrap()),
+        Some(Action::SaveDefault(Purpose::Inspect, Parts::DEFAULT))
+    );
+    let default_markup = crate::keyboard::markup(Purpose::Default, Parts::EVERYTHING);
+    let save = &default_markup.inline_keyboard[2][0];
+    assert_eq!(
+        Action::parse(save.callback_data.as_deref().unwrap()),
+        Some(Action::SaveDefault(Purpose::Default, Parts::EVERYTHING))
+    );
+    assert_eq!(Action::parse("save:hrmv"), None);
+}
@@ -1,21 +1,46 @@
 use std::fmt::Write as _;
+use std::path::Path;
 
 use anyhow::Context as _;
-use frankenstein::TelegramApi as _;
-use frankenstein::client_ureq::Bot;
-use frankenstein::methods::SendMessageParams;
-use frankenstein::types::{LinkPreviewOptions, ReplyParameters};
-use ureq::ResponseExt as _;
-use ureq::http::{HeaderName, header};
+use ureq::http::{HeaderName, Response, header};
+use ureq::{Body, ResponseExt as _};
 
+mod analyzer;
+mod bluesky;
+mod cli;
+mod command;
 mod ffmpeg;
+mod github;
+mod html;
 mod http;
+mod inline_query;
+mod instagram;
+mod json;
+mod json_document;
+mod json_ld;
+mod keyboard;
 mod macros;
+mod mastodon;
+mod opengraph;
+mod readability;
+mod reddit;
+mod report;
 mod single;
+mod split;
 mod telegram;
+mod telegram_preview;
+#[cfg(test)]
+mod test_server;
 mod tiktok;
+mod twitter;
+mod webhook;
+mod wikipedia;
+mod worker_pool;
+mod youtube;
 mod yt_dlp;
 
+use crate::report::{Report, Section};
+
 const INTERESTING_HEADERS: &[HeaderName] = &[
     header::CACHE_CONTROL,
     header::CONTENT_LENGTH,
@@ -25,95 +50,261 @@ const INTERESTING_HEADERS: &[HeaderName] = &[
     header::SERVER,
 ];
 
-fn main() {
+fn main() -> anyhow::Result<()> {
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        None => {}
+        Some("inspect") => return cli::inspect(args),
+        Some("-h" | "--help") => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Some(_) => anyhow::bail!("{}", cli::USAGE),
+    }
+
+    println!(
+        "Registered analyzers: {}",
+        analyzer::names().collect::<Vec<_>>().join(", ")
+    );
     let tg = telegram::Telegram::new();
+    if let Some(webhook) = webhook::Webhook::from_env() {
+        tg.start_webhook(&webhook, inspect_url);
+    }
     tg.start_polling_loop(inspect_url);
 }
 
+/// Which parts of [`inspect_url`] to run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[expect(clippy::struct_excessive_bools)]
+pub struct Parts {
+    /// Status and headers of the HTTP response
+    pub headers: bool,
+    /// Redirect history and the final url
+    pub redirects: bool,
+    /// Page and site specific [analyzers](analyzer::Analyzer)
+    pub metadata: bool,
+    /// Download of the media with yt-dlp
+    pub video: bool,
+    /// Download of only the audio with yt-dlp
+    pub audio: bool,
+    /// Response body as document
+    pub raw: bool,
+}
+
+impl Parts {
+    /// What is run when nothing else was chosen
+    pub const DEFAULT: Self = Self {
+        headers: true,
+        redirects: true,
+        metadata: true,
+        video: true,
+        ..Self::NONE
+    };
+    pub const EVERYTHING: Self = Self {
+        audio: true,
+        ..Self::DEFAULT
+    };
+    pub const NONE: Self = Self {
+        headers: false,
+        redirects: false,
+        metadata: false,
+        video: false,
+        audio: false,
+        raw: false,
+    };
+
+    const fn needs_response(self) -> bool {
+        self.headers || self.redirects || self.metadata || self.raw
+    }
+}
+
 fn inspect_url(
-    bot: &Bot,
-    chat_id: i64,
-    reply_params: &ReplyParameters,
     url: &str,
+    parts: Parts,
+    media_dir: &Path,
+    emit: &mut dyn FnMut(&'static str, Report) -> anyhow::Result<()>,
 ) -> anyhow::Result<()> {
-    println!("inspect_url {chat_id:>10}: {url}");
+    if !parts.needs_response() {
+        // yt-dlp follows redirects itself
+        download(url, parts, media_dir, emit)?;
+        return Ok(());
+    }
+
     let mut response = http::get(url).context("HTTP GET request failed")?;
     let body = response.body_mut().read_to_string();
-
     let target_uri = response.get_uri();
 
-    let mut meta = String::new();
-    if let Some(history) = response
-        .get_redirect_history()
-        .filter(|history| history.len() > 1)
-    {
-        writeln!(meta, "\nRedirect history:").unwrap();
-        for step in history {
-            writeln!(meta, "- {step}").unwrap();
-        }
+    if parts.headers || parts.redirects {
+        emit("http", http_report(&response, body.as_deref(), parts))?;
     }
-    if let Some(query) = target_uri.query() {
-        let without_query = target_uri.to_string().replace(&format!("?{query}"), "");
-        writeln!(meta, "\nWithout query: {without_query}").unwrap();
-    }
-    match &body {
-        Ok(body) => writeln!(meta, "\nBody is a string with length {}", body.len()).unwrap(),
-        Err(error) => writeln!(meta, "\nBody is not a string: {error:#}").unwrap(),
+
+    download(&target_uri.to_string(), parts, media_dir, emit)?;
+
+    if parts.raw {
+        emit("raw", raw_report(&response, body.as_deref()))?;
     }
-    bot.send_message(
-        &SendMessageParams::builder()
-            .link_preview_options(LinkPreviewOptions::DISABLED)
-            .chat_id(chat_id)
-            .reply_parameters(reply_params.clone())
-            .text(meta)
-            .build(),
-    )?;
-
-    let mut partial = format!("{:?} {}\n", response.version(), response.status());
-    for (key, value) in response.headers() {
-        let header = value.to_str().map_or_else(
-            |_| format!("{key}: {value:?}"),
-            |value| {
-                if value.len() <= 30 || INTERESTING_HEADERS.contains(key) {
-                    format!("{key}: {value}")
-                } else {
-                    format!("{key}: <omitted>")
-                }
-            },
-        );
-
-        // 4096 + safety
-        if partial.len().saturating_add(header.len()) > 4090 {
-            telegram::send_code(bot, chat_id, reply_params, None, Some("http"), &partial)?;
-            partial.clear();
+
+    let Ok(body) = body else {
+        return Ok(());
+    };
+
+    if parts.metadata {
+        let page = analyzer::Page {
+            uri: target_uri,
+            headers: response.headers(),
+            body: &body,
+        };
+        for analyzer in analyzer::matching(&page) {
+            let report = analyzer.analyze(&page).unwrap_or_else(|error| {
+                let mut report = Report::default();
+                report.error(format!("Failed with {}: {error:?}", analyzer.name()));
+                report
+            });
+            emit(analyzer.name(), report)?;
         }
-        partial += &header;
-        partial += "\n";
     }
-    if !partial.trim().is_empty() {
-        telegram::send_code(bot, chat_id, reply_params, None, Some("http"), &partial)?;
+
+    Ok(())
+}
+
+/// yt-dlp assumes every file in its directory is its output so video and audio get their own
+fn download(
+    url: &str,
+    parts: Parts,
+    media_dir: &Path,
+    emit: &mut dyn FnMut(&'static str, Report) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    if parts.video {
+        let dir = media_dir.join("video");
+        std::fs::create_dir_all(&dir).context("Should be able to create video dir")?;
+        emit("yt-dlp", yt_dlp::download(url, &dir))?;
     }
-    drop(partial);
-
-    if let Err(error) = yt_dlp::send_video(bot, chat_id, reply_params, &target_uri.to_string()) {
-        bot.send_message(
-            &SendMessageParams::builder()
-                .chat_id(chat_id)
-                .reply_parameters(reply_params.clone())
-                .text(format!("Failed with yt-dlp: {error:?}"))
-                .build(),
-        )?;
+    if parts.audio {
+        let dir = media_dir.join("audio");
+        std::fs::create_dir_all(&dir).context("Should be able to create audio dir")?;
+        emit("yt-dlp-audio", yt_dlp::download_audio(url, &dir))?;
     }
+    Ok(())
+}
 
-    let host = target_uri.host().context("Target URI should have a host")?;
+/// The data always contains everything, the sections only what the parts ask for
+fn http_report(
+    response: &Response<Body>,
+    body: Result<&str, &ureq::Error>,
+    parts: Parts,
+) -> Report {
+    let target_uri = response.get_uri();
 
-    let Ok(body) = body else {
-        return Ok(());
-    };
+    let mut report = Report::default();
+    report.insert_data("final_uri", target_uri.to_string());
+    report.insert_data(
+        "redirect_history",
+        response
+            .get_redirect_history()
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+    );
+    report.insert_data("version", format!("{:?}", response.version()));
+    report.insert_data("status", response.status().as_u16());
+    report.insert_data(
+        "headers",
+        response
+            .headers()
+            .iter()
+            .map(|(key, value)| {
+                serde_json::json!({
+                    "name": key.as_str(),
+                    "value": String::from_utf8_lossy(value.as_bytes()),
+                })
+            })
+            .collect::<Vec<_>>(),
+    );
+    report.insert_data("body_size", body.ok().map(str::len));
 
-    if host.ends_with("tiktok.com") {
-        tiktok::analyze(bot, chat_id, reply_params, &body).context("tiktok::analyze")?;
+    let mut text = String::new();
+    if parts.redirects {
+        writeln!(text, "Final url: {target_uri}").unwrap();
+        if let Some(history) = response
+            .get_redirect_history()
+            .filter(|history| history.len() > 1)
+        {
+            writeln!(text, "\nRedirect history:").unwrap();
+            for step in history {
+                writeln!(text, "- {step}").unwrap();
+            }
+        }
+        if let Some(without_query) = http::without_query(target_uri) {
+            writeln!(text, "\nWithout query: {without_query}").unwrap();
+        }
     }
+    if parts.headers {
+        match body {
+            Ok(body) => writeln!(text, "\nBody is a string with length {}", body.len()).unwrap(),
+            Err(error) => writeln!(text, "\nBody is not a string: {error:#}").unwrap(),
+        }
+    }
+    report.text(None, text.trim_start());
 
-    Ok(())
+    if parts.headers {
+        let rows = response
+            .headers()
+            .iter()
+            .map(|(key, value)| {
+                let value = value.to_str().map_or_else(
+                    |_| format!("{value:?}"),
+                    |value| {
+                        if value.len() <= 30 || INTERESTING_HEADERS.contains(key) {
+                            value.to_owned()
+                        } else {
+                            "<omitted>".to_owned()
+                        }
+                    },
+                );
+                (key.to_string(), value)
+            })
+            .collect();
+        report.push(Section::Table {
+            header: Some(format!("{:?} {}", response.version(), response.status())),
+            rows,
+        });
+    }
+    report
+}
+
+/// The response body as document named after its content type
+fn raw_report(response: &Response<Body>, body: Result<&str, &ureq::Error>) -> Report {
+    let mut report = Report::default();
+    let body = match body {
+        Ok(body) => body,
+        Err(error) => {
+            report.error(format!("Body is not a string: {error:#}"));
+            return report;
+        }
+    };
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+    let extension = if content_type.contains("html") {
+        "html"
+    } else if content_type.contains("json") {
+        "json"
+    } else if content_type.contains("xml") {
+        "xml"
+    } else {
+        "txt"
+    };
+    report.push(Section::Document {
+        filename: format!("body.{extension}"),
+        caption: Some(
+            format!("{content_type} ({} bytes)", body.len())
+                .trim_start()
+                .to_owned(),
+        ),
+        content: body.to_owned(),
+    });
+    report
 }
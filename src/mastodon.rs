@@ -0,0 +1,459 @@
+use std::fmt::Write as _;
+
+use chrono::DateTime;
+use scraper::Html;
+use serde_json::Value;
+use ureq::http::header::{ACCEPT, LINK};
+
+use crate::analyzer::{Analyzer, Page};
+use crate::html::html_to_text;
+use crate::json::deep_get;
+use crate::macros::selector;
+use crate::report::{Photo, Report};
+
+const ACTIVITY_JSON: &str = "application/activity+json";
+
+/// Object types of statuses. Profiles link to their `Person` the same way.
+const STATUS_TYPES: &[&str] = &[
+    "Note", "Article", "Question", "Page", "Video", "Audio", "Image",
+];
+
+/// Statuses of Mastodon and other fediverse servers.
+///
+/// Detected by the `Link` to the `application/activity+json` representation or by probing `/.well-known/nodeinfo`.
+pub struct Mastodon;
+
+impl Analyzer for Mastodon {
+    fn name(&self) -> &'static str {
+        "mastodon"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        // Parsing the HTML for the <link> is left to analyze
+        header_activity_link(page).is_some()
+            || looks_like_status(page.uri.path())
+            || page.body.contains(ACTIVITY_JSON)
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        let activity_url = match activity_link(page) {
+            Some(url) => url,
+            None if looks_like_status(page.uri.path()) && has_nodeinfo(page.uri) => {
+                page.uri.to_string()
+            }
+            // Not an ActivityPub server after all
+            None => return Ok(Report::default()),
+        };
+        let note = crate::http::get_json(&activity_url, &[(ACCEPT, ACTIVITY_JSON)])?;
+        if !is_status(&note) {
+            return Ok(Report::default());
+        }
+        let actor = note
+            .get("attributedTo")
+            .and_then(attributed_to)
+            .and_then(|actor| crate::http::get_json(actor, &[(ACCEPT, ACTIVITY_JSON)]).ok());
+        Ok(analyze(&note, actor.as_ref()))
+    }
+}
+
+/// URL of the `application/activity+json` representation from the `Link` header or `<link>` element
+fn activity_link(page: &Page) -> Option<String> {
+    header_activity_link(page).or_else(|| {
+        Html::parse_document(page.body)
+            .select(selector!(
+                r#"link[rel="alternate"][type="application/activity+json"][href]"#
+            ))
+            .find_map(|element| element.attr("href"))
+            .map(ToOwned::to_owned)
+    })
+}
+
+fn header_activity_link(page: &Page) -> Option<String> {
+    page.headers
+        .get_all(LINK)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .find(|link| link.contains("rel=\"alternate\"") && link.contains(ACTIVITY_JSON))
+        .and_then(|link| {
+            let start = link.find('<')?.saturating_add(1);
+            let end = link.find('>')?;
+            link.get(start..end)
+        })
+        .map(ToOwned::to_owned)
+}
+
+/// Paths like `/@user/123` or `/users/user/statuses/123` which are common for fediverse servers
+fn looks_like_status(path: &str) -> bool {
+    let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+    let is_id = |segment: &str| segment.bytes().all(|byte| byte.is_ascii_digit());
+    match segments.as_slice() {
+        [user, id] => user.starts_with('@') && is_id(id),
+        ["users", _, "statuses", id] => is_id(id),
+        _ => false,
+    }
+}
+
+fn has_nodeinfo(uri: &ureq::http::Uri) -> bool {
+    let (Some(scheme), Some(authority)) = (uri.scheme_str(), uri.authority()) else {
+        return false;
+    };
+    crate::http::get_json(&format!("{scheme}://{authority}/.well-known/nodeinfo"), &[])
+        .ok()
+        .and_then(|nodeinfo| {
+            nodeinfo
+                .get("links")
+                .and_then(Value::as_array)
+                .map(|links| !links.is_empty())
+        })
+        .unwrap_or(false)
+}
+
+/// The type might be given directly or as array
+fn is_status(object: &Value) -> bool {
+    match object.get("type") {
+        Some(Value::String(kind)) => STATUS_TYPES.contains(&kind.as_str()),
+        Some(Value::Array(kinds)) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|kind| STATUS_TYPES.contains(&kind)),
+        _ => false,
+    }
+}
+
+/// The actor id which might be given directly, as object or as array
+fn attributed_to(value: &Value) -> Option<&str> {
+    match value {
+        Value::String(id) => Some(id),
+        Value::Object(object) => object.get("id").and_then(Value::as_str),
+        Value::Array(array) => array.iter().find_map(attributed_to),
+        _ => None,
+    }
+}
+
+fn analyze(note: &Value, actor: Option<&Value>) -> Report {
+    let mut report = Report::default();
+    let field = |key| note.get(key).and_then(Value::as_str);
+
+    let author = author(note, actor);
+
+    if let Some(warning) = field("summary").filter(|summary| !summary.is_empty()) {
+        report.text(Some("content warning"), warning);
+    }
+    let content = field("content").map(html_to_text).unwrap_or_default();
+    report.text(Some(&author), content.clone());
+
+    let mut info = String::new();
+    for key in ["published", "updated"] {
+        if let Some(time) = field(key).and_then(|time| DateTime::parse_from_rfc3339(time).ok()) {
+            writeln!(info, "{key}: {}", time.to_utc()).unwrap();
+        }
+    }
+    if let Some(url) = field("url") {
+        writeln!(info, "url: {url}").unwrap();
+    }
+    if let Some(reply_to) = field("inReplyTo") {
+        writeln!(info, "inReplyTo: {reply_to}").unwrap();
+    }
+    let counts = ["replies", "likes", "shares"].map(|key| {
+        (
+            key,
+            deep_get(note, [key])
+                .and_then(|collection| collection.get("totalItems"))
+                .and_then(Value::as_u64),
+        )
+    });
+    for (key, count) in counts {
+        if let Some(count) = count {
+            writeln!(info, "{key}: {count}").unwrap();
+        }
+    }
+    report.text(None, info);
+
+    let attachments = note
+        .get("attachment")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut photos = Vec::new();
+    let mut others = String::new();
+    for attachment in attachments {
+        let media_type = attachment
+            .get("mediaType")
+            .and_then(Value::as_str)
+            .unwrap_or("unknown");
+        let Some(url) = attachment.get("url").and_then(Value::as_str) else {
+            continue;
+        };
+        let description = attachment
+            .get("name")
+            .and_then(Value::as_str)
+            .filter(|name| !name.is_empty());
+        if media_type.starts_with("image/") {
+            photos.push(Photo {
+                url: url.to_owned(),
+                caption: description.map(ToOwned::to_owned),
+            });
+        } else {
+            writeln!(others, "- {media_type}: {url}").unwrap();
+        }
+    }
+    if !others.is_empty() {
+        report.text(Some("attachments"), others);
+    }
+
+    report.insert_data("id", note.get("id").cloned());
+    report.insert_data("url", note.get("url").cloned());
+    report.insert_data("author", author);
+    report.insert_data("attributedTo", note.get("attributedTo").cloned());
+    report.insert_data("content", content);
+    report.insert_data("summary", note.get("summary").cloned());
+    report.insert_data("sensitive", note.get("sensitive").cloned());
+    report.insert_data("published", note.get("published").cloned());
+    report.insert_data("inReplyTo", note.get("inReplyTo").cloned());
+    report.insert_data("attachment", note.get("attachment").cloned());
+    for (key, count) in counts {
+        report.insert_data(key, count);
+    }
+
+    report.photos(photos);
+    report
+}
+
+/// Like `@user@example.com (Name)` or the actor id when the actor could not be fetched
+fn author(note: &Value, actor: Option<&Value>) -> String {
+    actor
+        .and_then(|actor| {
+            let username = actor.get("preferredUsername")?.as_str()?;
+            let host = actor
+                .get("id")
+                .and_then(Value::as_str)
+                .and_then(|id| id.parse::<ureq::http::Uri>().ok())
+                .and_then(|id| id.host().map(ToOwned::to_owned))
+                .unwrap_or_default();
+            let name = actor
+                .get("name")
+                .and_then(Value::as_str)
+                .filter(|name| !name.is_empty());
+            Some(name.map_or_else(
+                || format!("@{username}@{host}"),
+                |name| format!("@{username}@{host} ({name})"),
+            ))
+        })
+        .or_else(|| {
+            note.get("attributedTo")
+                .and_then(attributed_to)
+                .map(ToOwned::to_owned)
+        })
+        .unwrap_or_else(|| "unknown author".to_owned())
+}
+
+#[test]
+fn looks_like_status_works() {
+    assert!(looks_like_status("/@Gargron/113847291045192837"));
+    assert!(looks_like_status(
+        "/users/Gargron/statuses/113847291045192837"
+    ));
+    assert!(!looks_like_status("/@Gargron"));
+    assert!(!looks_like_status("/@user/some-medium-article-1a2b3c"));
+}
+
+#[test]
+fn activity_link_from_header_or_html() {
+    let uri = ureq::http::Uri::from_static("https://mastodon.social/@Gargron/1");
+    let mut headers = ureq::http::HeaderMap::new();
+    headers.insert(
+        LINK,
+        r#"<https://mastodon.social/users/Gargron/statuses/1>; rel="alternate"; type="application/activity+json", <https://mastodon.social/api/oembed>; rel="alternate"; type="application/json+oembed""#
+            .parse()
+            .unwrap(),
+    );
+    let page = Page {
+        uri: &uri,
+        headers: &headers,
+        body: "",
+    };
+    assert_eq!(
+        activity_link(&page).as_deref(),
+        Some("https://mastodon.social/users/Gargron/statuses/1")
+    );
+
+    let headers = ureq::http::HeaderMap::new();
+    let page = Page {
+        uri: &uri,
+        headers: &headers,
+        body: r#"<html><head><link href="https://mastodon.social/users/Gargron/statuses/1" rel="alternate" type="application/activity+json"></head></html>"#,
+    };
+    assert_eq!(
+        activity_link(&page).as_deref(),
+        Some("https://mastodon.social/users/Gargron/statuses/1")
+    );
+    assert!(Mastodon.matches(&page));
+
+    let uri = ureq::http::Uri::from_static("https://example.com/blog/post");
+    let page = Page {
+        uri: &uri,
+        headers: &headers,
+        body: "<html><head></head></html>",
+    };
+    assert!(!Mastodon.matches(&page));
+}
+
+#[test]
+fn probed_status_via_stand_in_server() {
+    use crate::report::Section;
+    use crate::test_server::{Route, TestServer};
+
+    let server = TestServer::new();
+    let base = server.url.clone();
+    let note = serde_json::json!({
+        "@context": ["https://www.w3.org/ns/activitystreams"],
+        "id": format!("{base}/users/alice/statuses/1"),
+        "type": "Note",
+        "summary": "food",
+        "inReplyTo": null,
+        "published": "2025-01-19T16:53:21Z",
+        "url": format!("{base}/@alice/1"),
+        "attributedTo": format!("{base}/users/alice"),
+        "sensitive": true,
+        "content": "<p>Pancakes<br>with syrup</p>",
+        "attachment": [
+            {"type": "Document", "mediaType": "image/jpeg", "url": format!("{base}/media/1.jpg"), "name": "A stack of pancakes"},
+            {"type": "Document", "mediaType": "video/mp4", "url": format!("{base}/media/2.mp4"), "name": null},
+        ],
+        "replies": {"type": "Collection", "totalItems": 2},
+        "likes": {"type": "Collection", "totalItems": 7},
+        "shares": {"type": "Collection", "totalItems": 3},
+    });
+    let actor = serde_json::json!({
+        "id": format!("{base}/users/alice"),
+        "type": "Person",
+        "preferredUsername": "alice",
+        "name": "Alice",
+    });
+    server.serve(vec![
+        Route::json(
+            "/.well-known/nodeinfo",
+            r#"{"links":[{"rel":"http://nodeinfo.diaspora.software/ns/schema/2.0","href":"/nodeinfo/2.0"}]}"#,
+        ),
+        Route::new("/@alice/1", ACTIVITY_JSON, note.to_string()).accept(ACTIVITY_JSON),
+        Route::new("/@alice/1", "text/html", "<html></html>"),
+        Route::new("/users/alice", ACTIVITY_JSON, actor.to_string()).accept(ACTIVITY_JSON),
+    ]);
+
+    // Without a Link header the server is detected by probing the nodeinfo
+    let uri = format!("{base}/@alice/1").parse().unwrap();
+    let headers = ureq::http::HeaderMap::new();
+    let page = Page {
+        uri: &uri,
+        headers: &headers,
+        body: "<html></html>",
+    };
+    assert!(Mastodon.matches(&page));
+    let report = Mastodon.analyze(&page).unwrap();
+
+    let host = uri.host().unwrap();
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text }
+            if *header == format!("@alice@{host} (Alice)") && text == "Pancakes\nwith syrup"
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text } if header == "content warning" && text == "food"
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: None, text }
+            if text.contains("published: 2025-01-19 16:53:21 UTC")
+                && text.contains("replies: 2")
+                && text.contains("shares: 3")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Photo { caption: Some(caption), .. } if caption == "A stack of pancakes"
+    )));
+}
+
+#[test]
+fn long_alt_text_fits_into_caption() {
+    use crate::report::Section;
+    use crate::split::CAPTION_LIMIT;
+
+    // Mastodon allows alt texts of up to 1500 characters
+    let name = "Pancakes with syrup. ".repeat(75);
+    let note = serde_json::json!({
+        "type": "Note",
+        "content": "<p>Pancakes</p>",
+        "attachment": [
+            {"type": "Document", "mediaType": "image/jpeg", "url": "https://example.com/1.jpg", "name": name},
+        ],
+    });
+    let report = analyze(&note, None);
+    let caption = report
+        .sections
+        .iter()
+        .find_map(|section| match section {
+            Section::Photo { caption, .. } => caption.as_deref(),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(caption, name);
+
+    let rendered = crate::telegram::photo_caption(caption);
+    assert!(rendered.encode_utf16().count() <= CAPTION_LIMIT);
+    assert!(rendered.starts_with("Pancakes with syrup."));
+    assert!(rendered.ends_with('…'));
+}
+
+#[test]
+fn no_nodeinfo_is_no_status() {
+    use crate::test_server::TestServer;
+
+    let server = TestServer::new();
+    let uri = format!("{}/@user/123", server.url).parse().unwrap();
+    server.serve(Vec::new());
+    let headers = ureq::http::HeaderMap::new();
+    let page = Page {
+        uri: &uri,
+        headers: &headers,
+        body: "",
+    };
+    assert!(Mastodon.analyze(&page).unwrap().sections.is_empty());
+}
+
+#[test]
+fn profile_is_no_status() {
+    use crate::test_server::{Route, TestServer};
+
+    let server = TestServer::new();
+    let base = server.url.clone();
+    let person = serde_json::json!({
+        "id": format!("{base}/users/alice"),
+        "type": "Person",
+        "preferredUsername": "alice",
+        "name": "Alice",
+    });
+    server.serve(vec![
+        Route::new("/users/alice", ACTIVITY_JSON, person.to_string()).accept(ACTIVITY_JSON),
+    ]);
+
+    let uri = format!("{base}/@alice").parse().unwrap();
+    let mut headers = ureq::http::HeaderMap::new();
+    headers.insert(
+        LINK,
+        format!(r#"<{base}/users/alice>; rel="alternate"; type="application/activity+json""#)
+            .parse()
+            .unwrap(),
+    );
+    let page = Page {
+        uri: &uri,
+        headers: &headers,
+        body: "<html></html>",
+    };
+    assert!(Mastodon.matches(&page));
+    assert!(Mastodon.analyze(&page).unwrap().sections.is_empty());
+}
@@ -0,0 +1,195 @@
+use std::fmt::Write as _;
+
+use scraper::Html;
+use serde_json::{Map, Value};
+use ureq::http::Uri;
+
+use crate::analyzer::{Analyzer, Page};
+use crate::macros::selector;
+use crate::report::{Report, Section};
+
+/// Generic page metadata like `<title>`, `og:*` and `twitter:*` properties
+pub struct OpenGraph;
+
+impl Analyzer for OpenGraph {
+    fn name(&self) -> &'static str {
+        "opengraph"
+    }
+
+    fn matches(&self, _page: &Page) -> bool {
+        true
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        Ok(analyze(page.uri, page.body))
+    }
+}
+
+#[derive(Debug, Default)]
+struct Metadata {
+    title: Option<String>,
+    description: Option<String>,
+    canonical: Option<String>,
+    /// `og:*` and `twitter:*` properties in document order
+    properties: Vec<(String, String)>,
+}
+
+impl Metadata {
+    fn property(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(property, _)| property == key)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+fn analyze(uri: &Uri, body: &str) -> Report {
+    let metadata = extract(body);
+    let mut report = Report::default();
+
+    let title = metadata.property("og:title").or(metadata.title.as_deref());
+    let description = metadata
+        .property("og:description")
+        .or(metadata.description.as_deref());
+
+    let mut summary = String::new();
+    if let Some(site_name) = metadata.property("og:site_name") {
+        writeln!(summary, "{site_name}").unwrap();
+    }
+    if let Some(title) = title {
+        writeln!(summary, "{title}").unwrap();
+    }
+    if let Some(description) = description {
+        writeln!(summary, "\n{description}").unwrap();
+    }
+    if let Some(canonical) = &metadata.canonical {
+        writeln!(summary, "\ncanonical: {canonical}").unwrap();
+    }
+    if !summary.is_empty() {
+        report.text(None, summary);
+    }
+
+    if !metadata.properties.is_empty() {
+        report.push(Section::Table {
+            header: Some("OpenGraph / Twitter Card".to_owned()),
+            rows: metadata.properties.clone(),
+        });
+    }
+
+    let image = metadata
+        .property("og:image")
+        .or_else(|| metadata.property("og:image:url"))
+        .or_else(|| metadata.property("twitter:image"));
+    if let Some(image) = image {
+        report.push(Section::Photo {
+            url: absolute_url(uri, image),
+            caption: title.map(ToOwned::to_owned),
+        });
+    }
+
+    report.insert_data("title", metadata.title.clone());
+    report.insert_data("description", metadata.description.clone());
+    report.insert_data("canonical", metadata.canonical.clone());
+    report.insert_data(
+        "properties",
+        metadata
+            .properties
+            .iter()
+            .map(|(key, value)| (key.clone(), Value::from(value.as_str())))
+            .collect::<Map<_, _>>(),
+    );
+
+    report
+}
+
+fn extract(body: &str) -> Metadata {
+    let html = Html::parse_document(body);
+    let mut metadata = Metadata {
+        title: html
+            .select(selector!("head title"))
+            .next()
+            .map(|element| element.text().collect::<String>().trim().to_owned())
+            .filter(|title| !title.is_empty()),
+        canonical: html
+            .select(selector!(r#"link[rel="canonical"][href]"#))
+            .next()
+            .and_then(|element| element.attr("href"))
+            .map(ToOwned::to_owned),
+        ..Metadata::default()
+    };
+
+    for element in html.select(selector!("meta[content]")) {
+        let Some(content) = element.attr("content").map(str::trim) else {
+            continue;
+        };
+        // Sites mix up name and property for both og and twitter
+        let Some(key) = element.attr("property").or_else(|| element.attr("name")) else {
+            continue;
+        };
+        if key.eq_ignore_ascii_case("description") {
+            metadata.description = Some(content.to_owned());
+        } else if key.starts_with("og:") || key.starts_with("twitter:") {
+            metadata
+                .properties
+                .push((key.to_owned(), content.to_owned()));
+        }
+    }
+
+    metadata
+}
+
+/// Resolve URLs like `/image.png` or `//cdn.example.com/image.png` against the page URI
+fn absolute_url(base: &Uri, url: &str) -> String {
+    let scheme = base.scheme_str().unwrap_or("https");
+    if url.starts_with("//") {
+        format!("{scheme}:{url}")
+    } else if url.starts_with('/') {
+        base.authority().map_or_else(
+            || url.to_owned(),
+            |authority| format!("{scheme}://{authority}{url}"),
+        )
+    } else {
+        url.to_owned()
+    }
+}
+
+#[test]
+fn extract_works() {
+    let html = r#"<html><head>
+        <title> Example Domain </title>
+        <meta name="description" content="Just an example">
+        <link rel="canonical" href="https://example.com/">
+        <meta property="og:title" content="Example">
+        <meta property="og:image" content="/preview.png">
+        <meta name="twitter:card" content="summary_large_image">
+    </head><body></body></html>"#;
+    let metadata = extract(html);
+    assert_eq!(metadata.title.as_deref(), Some("Example Domain"));
+    assert_eq!(metadata.description.as_deref(), Some("Just an example"));
+    assert_eq!(metadata.canonical.as_deref(), Some("https://example.com/"));
+    assert_eq!(metadata.property("og:title"), Some("Example"));
+    assert_eq!(
+        metadata.property("twitter:card"),
+        Some("summary_large_image")
+    );
+
+    let report = analyze(&Uri::from_static("https://example.com/page"), html);
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Photo { url, .. } if url == "https://example.com/preview.png"
+    )));
+}
+
+#[test]
+fn absolute_url_works() {
+    let base = Uri::from_static("https://example.com/some/page");
+    assert_eq!(
+        absolute_url(&base, "https://cdn.example.com/a.png"),
+        "https://cdn.example.com/a.png"
+    );
+    assert_eq!(
+        absolute_url(&base, "//cdn.example.com/a.png"),
+        "https://cdn.example.com/a.png"
+    );
+    assert_eq!(absolute_url(&base, "/a.png"), "https://example.com/a.png");
+}
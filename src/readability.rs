@@ -0,0 +1,402 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+use scraper::{ElementRef, Html};
+use ureq::http::header::CONTENT_TYPE;
+
+use crate::analyzer::{Analyzer, Page};
+use crate::macros::selector;
+use crate::report::{Report, Section};
+use crate::split::{MAX_CHUNKS, MESSAGE_LIMIT};
+
+/// Pages with less text are most likely not an article
+const MIN_WORDS: usize = 150;
+const WORDS_PER_MINUTE: usize = 200;
+
+/// Main article text of a page similar to the reader mode of browsers
+pub struct Readability;
+
+impl Analyzer for Readability {
+    fn name(&self) -> &'static str {
+        "readability"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        page.headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|content_type| content_type.contains("html"))
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        Ok(analyze(page.body))
+    }
+}
+
+#[derive(Debug, Default)]
+struct Article {
+    title: Option<String>,
+    byline: Option<String>,
+    published: Option<String>,
+    /// Markdown formatted blocks like paragraphs, headings and quotes
+    blocks: Vec<String>,
+}
+
+impl Article {
+    fn word_count(&self) -> usize {
+        self.blocks
+            .iter()
+            .map(|block| block.split_whitespace().count())
+            .sum()
+    }
+
+    fn markdown(&self) -> String {
+        let mut markdown = String::new();
+        if let Some(title) = &self.title {
+            writeln!(markdown, "# {title}\n").unwrap();
+        }
+        markdown += &self.blocks.join("\n\n");
+        markdown
+    }
+}
+
+fn analyze(body: &str) -> Report {
+    let mut report = Report::default();
+    let article = extract(body);
+    let words = article.word_count();
+    if words < MIN_WORDS {
+        return report;
+    }
+    let minutes = words.div_ceil(WORDS_PER_MINUTE);
+
+    let mut info = String::new();
+    if let Some(title) = &article.title {
+        writeln!(info, "{title}").unwrap();
+    }
+    if let Some(byline) = &article.byline {
+        writeln!(info, "by {byline}").unwrap();
+    }
+    if let Some(published) = &article.published {
+        writeln!(info, "published: {published}").unwrap();
+    }
+    writeln!(info, "{words} words, about {minutes} min read").unwrap();
+    report.text(Some("article"), info);
+
+    let text = article.blocks.join("\n\n");
+    // Longer articles are sent as a document instead of flooding the chat
+    if crate::split::split(&text, MESSAGE_LIMIT).len() > *MAX_CHUNKS {
+        report.push(Section::Document {
+            filename: "article.md".to_owned(),
+            caption: Some(format!(
+                "{} ({words} words)",
+                article.title.as_deref().unwrap_or("article")
+            )),
+            content: article.markdown(),
+        });
+    } else {
+        report.text(None, text.clone());
+    }
+
+    report.insert_data("title", article.title.clone());
+    report.insert_data("byline", article.byline.clone());
+    report.insert_data("published", article.published.clone());
+    report.insert_data("word_count", words);
+    report.insert_data("reading_minutes", minutes);
+    report.insert_data("text", text);
+
+    report
+}
+
+fn extract(html: &str) -> Article {
+    let document = Html::parse_document(html);
+    let Some(candidates) = top_candidates(&document) else {
+        return Article::default();
+    };
+
+    let mut blocks = Vec::new();
+    let mut taken = HashSet::new();
+    for candidate in candidates {
+        for element in candidate.select(selector!("p, h2, h3, h4, h5, h6, li, blockquote, pre")) {
+            if element
+                .ancestors()
+                .any(|ancestor| taken.contains(&ancestor.id()))
+                || is_unlikely(element)
+                || link_density(element) > 0.5
+            {
+                continue;
+            }
+            let text = collapse_whitespace(element);
+            if text.is_empty() {
+                continue;
+            }
+            taken.insert(element.id());
+            let block = match element.value().name() {
+                "h2" | "h3" | "h4" | "h5" | "h6" => format!("## {text}"),
+                "li" => format!("- {text}"),
+                "blockquote" => format!("> {text}"),
+                "pre" => format!(
+                    "```\n{}\n```",
+                    element.text().collect::<String>().trim_end()
+                ),
+                _ => text,
+            };
+            blocks.push(block);
+        }
+    }
+
+    Article {
+        title: meta(&document, "og:title").or_else(|| {
+            document
+                .select(selector!("h1, title"))
+                .map(collapse_whitespace)
+                .find(|title| !title.is_empty())
+        }),
+        byline: byline(&document),
+        published: published(&document),
+        blocks,
+    }
+}
+
+/// The element with the highest score and its siblings which score similarly well
+fn top_candidates(document: &Html) -> Option<Vec<ElementRef<'_>>> {
+    let mut scores = HashMap::new();
+    for paragraph in document.select(selector!("p, pre, td")) {
+        let text = collapse_whitespace(paragraph);
+        if text.chars().count() < 25
+            || paragraph
+                .ancestors()
+                .filter_map(ElementRef::wrap)
+                .any(is_unlikely)
+        {
+            continue;
+        }
+        let score = paragraph_score(&text);
+        let mut ancestors = paragraph.ancestors().filter_map(ElementRef::wrap);
+        if let Some(parent) = ancestors.next() {
+            *scores
+                .entry(parent.id())
+                .or_insert_with(|| initial_score(parent)) += score;
+        }
+        if let Some(grandparent) = ancestors.next() {
+            *scores
+                .entry(grandparent.id())
+                .or_insert_with(|| initial_score(grandparent)) += score / 2.0;
+        }
+    }
+
+    let weighted = |id| {
+        let element = ElementRef::wrap(document.tree.get(id)?)?;
+        Some((element, scores[&id] * (1.0 - link_density(element))))
+    };
+    let (top, top_score) = scores
+        .keys()
+        .filter_map(|id| weighted(*id))
+        .max_by(|(_, left), (_, right)| left.total_cmp(right))?;
+
+    let threshold = (top_score * 0.2).max(10.0);
+    let parent = top.parent().and_then(ElementRef::wrap);
+    let candidates = parent.map_or_else(
+        || vec![top],
+        |parent| {
+            parent
+                .children()
+                .filter_map(ElementRef::wrap)
+                .filter(|sibling| {
+                    sibling.id() == top.id()
+                        || scores.contains_key(&sibling.id())
+                            && weighted(sibling.id()).is_some_and(|(_, score)| score >= threshold)
+                })
+                .collect()
+        },
+    );
+    Some(candidates)
+}
+
+#[expect(clippy::cast_precision_loss)]
+fn paragraph_score(text: &str) -> f64 {
+    let commas = text.matches(',').count() as f64;
+    let length = (text.chars().count() / 100).min(3) as f64;
+    1.0 + commas + length
+}
+
+fn initial_score(element: ElementRef) -> f64 {
+    let tag = match element.value().name() {
+        "article" => 10.0,
+        "div" | "main" => 5.0,
+        "blockquote" | "pre" | "td" => 3.0,
+        "form" | "ol" | "ul" | "dl" | "dd" | "dt" | "li" => -3.0,
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" | "th" => -5.0,
+        _ => 0.0,
+    };
+    tag + class_weight(element)
+}
+
+fn class_weight(element: ElementRef) -> f64 {
+    let value = element.value();
+    let names = format!(
+        "{} {}",
+        value.attr("class").unwrap_or_default(),
+        value.id().unwrap_or_default()
+    );
+    let mut weight = 0.0;
+    if lazy_regex::regex_is_match!(
+        r"article|body|content|entry|main|page|post|story|text"i,
+        &names
+    ) {
+        weight += 25.0;
+    }
+    if lazy_regex::regex_is_match!(
+        r"ad-|banner|comment|footer|header|menu|nav|promo|related|share|sidebar|social|sponsor|widget"i,
+        &names
+    ) {
+        weight -= 25.0;
+    }
+    weight
+}
+
+/// Page chrome which never contains the article text
+fn is_unlikely(element: ElementRef) -> bool {
+    matches!(
+        element.value().name(),
+        "aside" | "footer" | "form" | "header" | "nav"
+    ) || lazy_regex::regex_is_match!(
+        r"byline|comment|footer|menu|nav|promo|related|sidebar|sponsor"i,
+        element.value().attr("class").unwrap_or_default()
+    )
+}
+
+/// Share of the text which is inside of links
+#[expect(clippy::cast_precision_loss)]
+fn link_density(element: ElementRef) -> f64 {
+    let length = element.text().map(str::len).sum::<usize>();
+    if length == 0 {
+        return 0.0;
+    }
+    let links = element
+        .select(selector!("a"))
+        .flat_map(|link| link.text())
+        .map(str::len)
+        .sum::<usize>();
+    links as f64 / length as f64
+}
+
+fn collapse_whitespace(element: ElementRef) -> String {
+    element
+        .text()
+        .flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn meta(document: &Html, property: &str) -> Option<String> {
+    document
+        .select(selector!("meta[property], meta[name], meta[itemprop]"))
+        .find(|element| {
+            let value = element.value();
+            [
+                value.attr("property"),
+                value.attr("name"),
+                value.attr("itemprop"),
+            ]
+            .contains(&Some(property))
+        })
+        .and_then(|element| element.value().attr("content"))
+        .map(str::trim)
+        .filter(|content| !content.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+fn byline(document: &Html) -> Option<String> {
+    meta(document, "author")
+        .or_else(|| meta(document, "article:author"))
+        .or_else(|| {
+            document
+                .select(selector!(
+                    r#"[rel="author"], [itemprop="author"], .byline, .author"#
+                ))
+                .map(collapse_whitespace)
+                .find(|text| !text.is_empty())
+        })
+        .map(|byline| {
+            byline
+                .strip_prefix("By ")
+                .or_else(|| byline.strip_prefix("by "))
+                .unwrap_or(&byline)
+                .to_owned()
+        })
+}
+
+fn published(document: &Html) -> Option<String> {
+    ["article:published_time", "datePublished", "date", "pubdate"]
+        .into_iter()
+        .find_map(|property| meta(document, property))
+        .or_else(|| {
+            document
+                .select(selector!("time[datetime]"))
+                .find_map(|element| element.value().attr("datetime"))
+                .map(ToOwned::to_owned)
+        })
+}
+
+#[test]
+fn harz_hiking_report() {
+    let html = include_str!("../test/article-harz-hiking.html");
+    let report = analyze(html);
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text }
+            if header == "article"
+                && text.starts_with("Hiking the Brocken in Winter\nby Hanna Harz\n")
+                && text.contains("published: 2025-01-19T16:53:21+01:00")
+                && text.contains("about 2 min read")
+    )));
+
+    let text = report.data["text"].as_str().unwrap();
+    assert!(text.starts_with("The Brocken is the highest peak"));
+    assert!(text.contains("\n\n## The ascent\n\n"));
+    assert!(text.contains("\n\n> Whoever climbs the Brocken"));
+    assert!(text.ends_with("no fun at all."));
+    for chrome in [
+        "newsletter",
+        "trail map",
+        "Related posts",
+        "Great post",
+        "Copyright",
+        "By Hanna",
+    ] {
+        assert!(!text.contains(chrome), "should not contain {chrome}");
+    }
+
+    let messages = report
+        .sections
+        .iter()
+        .filter(|section| matches!(section, Section::Text { header: None, .. }))
+        .count();
+    assert_eq!(messages, 1);
+}
+
+#[test]
+fn long_article_is_a_document() {
+    let paragraph = "<p>Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor incididunt ut labore et dolore magna aliqua.</p>";
+    let html = format!(
+        "<html><head><title>Lorem</title></head><body><article>{}</article></body></html>",
+        paragraph.repeat(200)
+    );
+    let report = analyze(&html);
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Document { filename, caption: Some(caption), content }
+            if filename == "article.md"
+                && caption == "Lorem (3800 words)"
+                && content.starts_with("# Lorem\n\nLorem ipsum")
+    )));
+}
+
+#[test]
+fn no_article_is_an_empty_report() {
+    let report = analyze(
+        "<html><body><nav><p>Home, Trails, About and a lot more links to everywhere.</p></nav></body></html>",
+    );
+    assert!(report.sections.is_empty());
+    assert!(report.data.is_empty());
+}
@@ -0,0 +1,217 @@
+use std::fmt::Write as _;
+
+use anyhow::Context as _;
+use serde_json::Value;
+
+use crate::analyzer::{Analyzer, Page, host_is};
+use crate::json::{deep_get, parse_timestamp};
+use crate::report::{Photo, Report};
+
+/// Amount of top level comments shown
+const TOP_COMMENTS: usize = 5;
+
+pub struct Reddit;
+
+impl Analyzer for Reddit {
+    fn name(&self) -> &'static str {
+        "reddit"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        let uri = page.uri;
+        host_is(uri, "reddit.com") && uri.path().contains("/comments/")
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        let authority = page.uri.authority().context("Should have an authority")?;
+        let path = page.uri.path().trim_end_matches('/');
+        let json = crate::http::get_json(&format!("https://{authority}{path}.json"), &[])?;
+        analyze(&json)
+    }
+}
+
+fn analyze(json: &Value) -> anyhow::Result<Report> {
+    let post = json
+        .get(0)
+        .and_then(|listing| deep_get(listing, ["data", "children"]))
+        .and_then(|children| children.get(0))
+        .and_then(|child| child.get("data"))
+        .context("Should have a post")?;
+    let mut report = Report::default();
+
+    let field = |key| post.get(key).and_then(Value::as_str);
+
+    let mut info = String::new();
+    if let Some(title) = field("title") {
+        writeln!(info, "{title}\n").unwrap();
+    }
+    if let Some(subreddit) = field("subreddit_name_prefixed") {
+        writeln!(info, "subreddit: {subreddit}").unwrap();
+    }
+    if let Some(author) = field("author") {
+        writeln!(info, "author: u/{author}").unwrap();
+    }
+    if let Some(flair) = field("link_flair_text").filter(|flair| !flair.is_empty()) {
+        writeln!(info, "flair: {flair}").unwrap();
+    }
+    if let Some(time) = post.get("created_utc").and_then(parse_timestamp) {
+        writeln!(info, "created: {time}").unwrap();
+    }
+    if let Some(score) = post.get("score").and_then(Value::as_i64) {
+        writeln!(info, "score: {score}").unwrap();
+    }
+    if let Some(ratio) = post.get("upvote_ratio").and_then(Value::as_f64) {
+        writeln!(info, "upvote_ratio: {ratio}").unwrap();
+    }
+    if let Some(comments) = post.get("num_comments").and_then(Value::as_u64) {
+        writeln!(info, "num_comments: {comments}").unwrap();
+    }
+    let is_self = post.get("is_self").and_then(Value::as_bool) == Some(true);
+    let linked = field("url").filter(|_| !is_self);
+    if let Some(url) = linked {
+        writeln!(info, "url: {url}").unwrap();
+    }
+    report.text(None, info);
+
+    if let Some(selftext) = field("selftext").filter(|text| !text.is_empty()) {
+        report.text(Some("selftext"), selftext);
+    }
+
+    let gallery = gallery(post);
+    report.insert_data(
+        "gallery",
+        gallery
+            .iter()
+            .map(|photo| photo.url.clone())
+            .collect::<Vec<_>>(),
+    );
+    report.photos(gallery);
+
+    if post.get("is_video").and_then(Value::as_bool) == Some(true)
+        || field("domain") == Some("v.redd.it")
+    {
+        // yt-dlp downloads v.redd.it videos of the post
+        report.text(
+            None,
+            "Contains a v.redd.it video, use /video to download it",
+        );
+    }
+
+    let comments = top_comments(json);
+    if !comments.is_empty() {
+        let mut code = String::new();
+        for (author, score, body) in &comments {
+            writeln!(code, "u/{author} ({score}):\n{body}\n").unwrap();
+        }
+        report.code(Some("top comments"), None, code.trim_end());
+    }
+
+    for key in [
+        "title",
+        "subreddit",
+        "author",
+        "score",
+        "upvote_ratio",
+        "link_flair_text",
+        "created_utc",
+        "selftext",
+        "url",
+        "num_comments",
+        "permalink",
+    ] {
+        report.insert_data(key, post.get(key).cloned());
+    }
+    report.insert_data(
+        "top_comments",
+        comments
+            .iter()
+            .map(|(author, score, body)| {
+                serde_json::json!({"author": author, "score": score, "body": body})
+            })
+            .collect::<Vec<_>>(),
+    );
+
+    Ok(report)
+}
+
+/// Images of a gallery post in gallery order
+fn gallery(post: &Value) -> Vec<Photo> {
+    let Some(metadata) = post.get("media_metadata") else {
+        return Vec::new();
+    };
+    deep_get(post, ["gallery_data", "items"])
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|item| {
+            let id = item.get("media_id")?.as_str()?;
+            let url = deep_get(metadata.get(id)?, ["s", "u"])?.as_str()?;
+            Some(Photo {
+                // The JSON contains HTML escaped URLs
+                url: url.replace("&amp;", "&"),
+                caption: item
+                    .get("caption")
+                    .and_then(Value::as_str)
+                    .map(ToOwned::to_owned),
+            })
+        })
+        .collect()
+}
+
+/// Author, score and body of the top level comments
+fn top_comments(json: &Value) -> Vec<(String, i64, String)> {
+    json.get(1)
+        .and_then(|listing| deep_get(listing, ["data", "children"]))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|child| child.get("kind").and_then(Value::as_str) == Some("t1"))
+        .filter_map(|child| {
+            let data = child.get("data")?;
+            Some((
+                data.get("author")?.as_str()?.to_owned(),
+                data.get("score")?.as_i64()?,
+                data.get("body")?.as_str()?.to_owned(),
+            ))
+        })
+        .take(TOP_COMMENTS)
+        .collect()
+}
+
+#[test]
+fn fixture_report() {
+    use crate::report::Section;
+
+    let json = include_str!("../test/reddit-1i4xyz9.json");
+    let report = analyze(&serde_json::from_str(json).unwrap()).unwrap();
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: None, text }
+            if text.starts_with("Pictures from RustConf hallway track\n")
+                && text.contains("subreddit: r/rust")
+                && text.contains("flair: 🛠️ project")
+                && text.contains("created: 2025-01-19 16:53:21 UTC")
+                && text.contains("upvote_ratio: 0.97")
+    )));
+    let photos = report
+        .sections
+        .iter()
+        .find_map(|section| match section {
+            Section::PhotoGroup(photos) => Some(photos),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(photos.len(), 2);
+    assert_eq!(
+        photos[0].url,
+        "https://preview.redd.it/abc123.jpg?width=4032&format=pjpg&auto=webp&s=1a"
+    );
+    assert_eq!(photos[0].caption.as_deref(), Some("The crab"));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Code { header: Some(header), code, .. }
+            if header == "top comments"
+                && code.starts_with("u/crab_enjoyer (120):\nLove the second one!\n")
+                && !code.contains("Yes it was")
+    )));
+}
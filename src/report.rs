@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use serde_json::{Map, Value};
+
+/// Frontend independent result of inspecting something.
+///
+/// Frontends like [`crate::telegram::send_report`] decide how to present it.
+#[derive(Debug, Default)]
+pub struct Report {
+    pub sections: Vec<Section>,
+    /// Machine readable details for the [JSON document](crate::json_document)
+    pub data: Map<String, Value>,
+}
+
+#[derive(Debug)]
+pub enum Section {
+    Text {
+        header: Option<String>,
+        text: String,
+    },
+    Code {
+        header: Option<String>,
+        language: Option<&'static str>,
+        code: String,
+    },
+    /// Structured data which is shown pretty printed
+    Json {
+        header: String,
+        value: Value,
+    },
+    Table {
+        header: Option<String>,
+        rows: Vec<(String, String)>,
+    },
+    Photo {
+        url: String,
+        caption: Option<String>,
+    },
+    /// Multiple photos which belong together like a carousel or gallery
+    PhotoGroup(Vec<Photo>),
+    Audio(Audio),
+    /// Downloaded audio which has its details embedded
+    AudioFile(PathBuf),
+    Video(Video),
+    /// Content which is better read as a file than as messages
+    Document {
+        filename: String,
+        caption: Option<String>,
+        content: String,
+    },
+    /// Original Telegram post which frontends with access might forward
+    TelegramPost {
+        chat: String,
+        message_id: i32,
+    },
+    Error(String),
+}
+
+#[derive(Debug)]
+pub struct Photo {
+    pub url: String,
+    pub caption: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Audio {
+    pub url: String,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    /// In seconds
+    pub duration: Option<u32>,
+    pub caption: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Video {
+    pub path: PathBuf,
+    pub height: u32,
+    pub width: u32,
+    /// In seconds
+    pub duration: u32,
+}
+
+impl Report {
+    pub fn push(&mut self, section: Section) {
+        self.sections.push(section);
+    }
+
+    pub fn text(&mut self, header: Option<&str>, text: impl Into<String>) {
+        self.push(Section::Text {
+            header: header.map(ToOwned::to_owned),
+            text: text.into(),
+        });
+    }
+
+    pub fn code(
+        &mut self,
+        header: Option<&str>,
+        language: Option<&'static str>,
+        code: impl Into<String>,
+    ) {
+        self.push(Section::Code {
+            header: header.map(ToOwned::to_owned),
+            language,
+            code: code.into(),
+        });
+    }
+
+    /// Single photo or a group of them
+    pub fn photos(&mut self, mut photos: Vec<Photo>) {
+        if photos.len() > 1 {
+            self.push(Section::PhotoGroup(photos));
+        } else if let Some(photo) = photos.pop() {
+            self.push(Section::Photo {
+                url: photo.url,
+                caption: photo.caption,
+            });
+        }
+    }
+
+    pub fn insert_data(&mut self, key: &str, value: impl Into<Value>) {
+        self.data.insert(key.to_owned(), value.into());
+    }
+
+    pub fn error(&mut self, error: impl Into<String>) {
+        self.push(Section::Error(error.into()));
+    }
+}
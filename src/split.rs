@@ -0,0 +1,118 @@
+//! Splitting of long texts into message sized chunks
+
+use std::sync::LazyLock;
+
+/// Telegram allows up to 4096 UTF-16 code units per message
+pub const MESSAGE_LIMIT: usize = 4096;
+
+/// Telegram allows up to 1024 UTF-16 code units in a photo caption
+pub const CAPTION_LIMIT: usize = 1024;
+
+const DEFAULT_MAX_CHUNKS: usize = 4;
+
+/// Texts needing more messages than this are better sent as a document.
+///
+/// Can be configured with `MAX_MESSAGES`.
+pub static MAX_CHUNKS: LazyLock<usize> = LazyLock::new(|| {
+    std::env::var("MAX_MESSAGES")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|max| *max > 0)
+        .unwrap_or(DEFAULT_MAX_CHUNKS)
+});
+
+/// Split the text into chunks of at most `limit` UTF-16 code units.
+///
+/// Chunks end on line boundaries, only lines longer than the limit are split in between.
+/// Newlines at the boundaries of chunks are dropped.
+pub fn split(text: &str, limit: usize) -> Vec<&str> {
+    let mut chunks = Vec::new();
+    let mut push = |start: usize, end: usize| {
+        let chunk = text[start..end].trim_matches('\n');
+        if !chunk.is_empty() {
+            chunks.push(chunk);
+        }
+    };
+
+    let mut start = 0;
+    let mut length = 0;
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let line_length = line.encode_utf16().count();
+        if length > 0 && length + line_length > limit {
+            push(start, line_start);
+            start = line_start;
+            length = 0;
+        }
+        if line_length > limit {
+            for (index, character) in line.char_indices() {
+                if length + character.len_utf16() > limit {
+                    push(start, line_start + index);
+                    start = line_start + index;
+                    length = 0;
+                }
+                length += character.len_utf16();
+            }
+        } else {
+            length += line_length;
+        }
+        line_start += line.len();
+    }
+    push(start, text.len());
+    chunks
+}
+
+/// Cut the text to at most `limit` UTF-16 code units and mark the cut with an ellipsis.
+pub fn truncate(text: &str, limit: usize) -> String {
+    if text.encode_utf16().count() <= limit {
+        return text.to_owned();
+    }
+    let mut length = '…'.len_utf16();
+    let end = text
+        .char_indices()
+        .find(|(_, character)| {
+            length += character.len_utf16();
+            length > limit
+        })
+        .map_or(text.len(), |(index, _)| index);
+    format!("{}…", text[..end].trim_end())
+}
+
+#[test]
+fn short_text_is_single_chunk() {
+    assert_eq!(split("hello\nworld\n", 100), ["hello\nworld"]);
+    assert!(split("", 100).is_empty());
+}
+
+#[test]
+fn splits_on_line_boundaries() {
+    assert_eq!(
+        split("aaaa\nbbbb\ncccc\ndddd", 10),
+        ["aaaa\nbbbb", "cccc\ndddd"]
+    );
+    assert_eq!(split("aaaa\n\n\nbbbb", 6), ["aaaa", "bbbb"]);
+}
+
+#[test]
+fn splits_long_lines() {
+    assert_eq!(split("aaaaaaaaaa\nbb", 4), ["aaaa", "aaaa", "aa", "bb"]);
+}
+
+#[test]
+fn counts_utf16_code_units() {
+    // Emoji need two UTF-16 code units
+    assert_eq!(split("🦀🦀🦀", 4), ["🦀🦀", "🦀"]);
+    assert_eq!(split("äöü\n🦀🦀", 4), ["äöü", "🦀🦀"]);
+    for chunk in split(&"🦀 ".repeat(5000), MESSAGE_LIMIT) {
+        assert!(chunk.encode_utf16().count() <= MESSAGE_LIMIT);
+    }
+}
+
+#[test]
+fn truncate_works() {
+    assert_eq!(truncate("hello", 5), "hello");
+    assert_eq!(truncate("hello world", 8), "hello w…");
+    assert_eq!(truncate("🦀🦀🦀", 4), "🦀…");
+    let long = truncate(&"🦀".repeat(1000), CAPTION_LIMIT);
+    assert!(long.encode_utf16().count() <= CAPTION_LIMIT);
+}
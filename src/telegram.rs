@@ -1,14 +1,56 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::sync::Mutex;
+
 use anyhow::Context as _;
 use frankenstein::TelegramApi as _;
 use frankenstein::client_ureq::Bot;
-use frankenstein::methods::{GetUpdatesParams, LeaveChatParams, SendMessageParams};
+use frankenstein::inline_mode::InlineQuery;
+use frankenstein::input_media::{InputMediaPhoto, MediaGroupInputMedia};
+use frankenstein::methods::{
+    AnswerCallbackQueryParams, AnswerInlineQueryParams, DeleteMessageParams, DeleteWebhookParams,
+    EditMessageTextParams, ForwardMessageParams, GetUpdatesParams, LeaveChatParams,
+    SendAudioParams, SendChatActionParams, SendDocumentParams, SendMediaGroupParams,
+    SendMessageParams, SendPhotoParams, SendVideoParams, SetMyCommandsParams, SetWebhookParams,
+};
 use frankenstein::response::MethodResponse;
-use frankenstein::types::{ChatType, Message, MessageEntity, MessageEntityType, ReplyParameters};
-use frankenstein::updates::UpdateContent;
+use frankenstein::types::{
+    BotCommand, CallbackQuery, Chat, ChatAction, ChatType, InlineKeyboardMarkup,
+    LinkPreviewOptions, MaybeInaccessibleMessage, Message, MessageEntity, MessageEntityType,
+    ReplyMarkup, ReplyParameters, User,
+};
+use frankenstein::updates::{Update, UpdateContent};
+
+use crate::command::{COMMANDS, Command, HELP};
+use crate::json_document::JsonDocument;
+use crate::keyboard::{self, Action, Purpose};
+use crate::report::{Photo, Report, Section, Video};
+use crate::split::{CAPTION_LIMIT, MAX_CHUNKS, MESSAGE_LIMIT};
+use crate::webhook::Webhook;
+use crate::worker_pool::{WORKERS, WorkerPool};
+use crate::{Parts, inline_query};
+
+/// Inspect the url, store downloaded media in the directory and emit each [`Report`] once it is ready
+pub type InspectUrl = fn(
+    &str,
+    Parts,
+    &Path,
+    &mut dyn FnMut(&'static str, Report) -> anyhow::Result<()>,
+) -> anyhow::Result<()>;
+
+/// Room for the header of a split code message like `header (12/34):\n`
+const CODE_HEADER_RESERVE: usize = 16;
 
 pub struct Telegram {
     bot: Bot,
+    /// The bot itself to know when it is meant in groups
+    me: User,
     allowed_users: Vec<i64>,
+    /// Parts preselected on the keyboard per chat
+    defaults: Mutex<HashMap<i64, Parts>>,
+    /// File ids of already sent videos by their url for inline queries
+    videos: Mutex<HashMap<String, String>>,
 }
 
 impl Telegram {
@@ -35,24 +77,41 @@ impl Telegram {
         let me = bot
             .get_me()
             .expect("Should be able to get_me with the BOT_TOKEN");
-        let username = me.result.username.expect("Bot should have a username");
+        let me = me.result;
+        let username = me.username.as_deref().expect("Bot should have a username");
         println!("Telegram Bot acts as @{username}");
 
-        bot.delete_my_commands(&frankenstein::methods::DeleteMyCommandsParams::builder().build())
-            .expect("Should be able to delete_my_commands");
+        let commands = COMMANDS
+            .iter()
+            .map(|(command, description)| {
+                BotCommand::builder()
+                    .command(*command)
+                    .description(*description)
+                    .build()
+            })
+            .collect();
+        bot.set_my_commands(&SetMyCommandsParams::builder().commands(commands).build())
+            .expect("Should be able to set_my_commands");
 
-        Self { bot, allowed_users }
+        Self {
+            bot,
+            me,
+            allowed_users,
+            defaults: Mutex::new(HashMap::new()),
+            videos: Mutex::new(HashMap::new()),
+        }
     }
 
-    pub fn start_polling_loop<F>(&self, inspect_url: F) -> !
-    where
-        F: Fn(&Bot, i64, &ReplyParameters, &str) -> anyhow::Result<()>,
-    {
+    pub fn start_polling_loop(&self, inspect_url: InspectUrl) -> ! {
+        // A webhook registered by an earlier run prevents get_updates
+        self.bot
+            .delete_webhook(&DeleteWebhookParams::builder().build())
+            .expect("Should be able to delete_webhook before polling");
         let mut get_updates_worked = true;
         let mut get_updates_params = GetUpdatesParams::builder().timeout(300).build();
-        loop {
+        self.dispatch(inspect_url, || {
             let updates = match self.bot.get_updates(&get_updates_params) {
-                Ok(updates) => updates,
+                Ok(updates) => updates.result,
                 Err(error) => {
                     // When it didnt work last time, fully error out
                     assert!(
@@ -61,65 +120,177 @@ impl Telegram {
                     );
                     get_updates_worked = false;
                     eprintln!("Error while Telegram bot get_updates: {error:#}");
-                    continue;
+                    return Vec::new();
                 }
             };
             get_updates_worked = true;
-
-            for update in updates.result {
+            if let Some(update) = updates.last() {
                 get_updates_params.offset = Some(i64::from(update.update_id).saturating_add(1));
-                match update.content {
-                    UpdateContent::ChannelPost(message)
-                    | UpdateContent::EditedChannelPost(message) => {
-                        if matches!(message.chat.type_field, ChatType::Channel) {
-                            self.leave_channel(message.chat.id);
-                        }
-                    }
-                    UpdateContent::MyChatMember(chat_member_updated)
-                    | UpdateContent::ChatMember(chat_member_updated) => {
-                        if matches!(chat_member_updated.chat.type_field, ChatType::Channel) {
-                            self.leave_channel(chat_member_updated.chat.id);
-                        }
+            }
+            updates
+        })
+    }
+
+    /// Register the webhook with Telegram when it has a public url and handle what arrives
+    pub fn start_webhook(&self, webhook: &Webhook, inspect_url: InspectUrl) -> ! {
+        if let Some(url) = &webhook.url {
+            self.bot
+                .set_webhook(
+                    &SetWebhookParams::builder()
+                        .url(url)
+                        .secret_token(&webhook.secret)
+                        .build(),
+                )
+                .expect("Should be able to set_webhook with WEBHOOK_URL");
+        }
+        self.dispatch(inspect_url, || vec![webhook.next_update()])
+    }
+
+    /// Pass the updates to the workers.
+    ///
+    /// Inline queries have their own workers as Telegram only waits a few seconds for their answer.
+    fn dispatch(
+        &self,
+        inspect_url: InspectUrl,
+        mut next_updates: impl FnMut() -> Vec<Update>,
+    ) -> ! {
+        std::thread::scope(|scope| -> ! {
+            let chats = WorkerPool::new(scope, *WORKERS, |update| {
+                self.handle_update(update, inspect_url);
+            });
+            let inline_queries = WorkerPool::new(scope, *WORKERS, |query: InlineQuery| {
+                if let Err(error) = self.answer_inline_query(&query) {
+                    eprintln!("Failed to answer inline query: {error:#}");
+                }
+            });
+            loop {
+                for update in next_updates() {
+                    self.receive(&chats, &inline_queries, update, inspect_url);
+                }
+            }
+        })
+    }
+
+    /// Handle quick updates right away and queue the others per chat to keep their order
+    fn receive(
+        &self,
+        chats: &WorkerPool<i64, Update>,
+        inline_queries: &WorkerPool<u64, InlineQuery>,
+        update: Update,
+        inspect_url: InspectUrl,
+    ) {
+        match update.content {
+            UpdateContent::ChannelPost(message) | UpdateContent::EditedChannelPost(message) => {
+                if matches!(message.chat.type_field, ChatType::Channel) {
+                    self.leave_channel(message.chat.id);
+                }
+            }
+            UpdateContent::MyChatMember(chat_member_updated)
+            | UpdateContent::ChatMember(chat_member_updated) => {
+                if matches!(chat_member_updated.chat.type_field, ChatType::Channel) {
+                    self.leave_channel(chat_member_updated.chat.id);
+                }
+            }
+            UpdateContent::Message(ref message) | UpdateContent::EditedMessage(ref message) => {
+                if !is_addressed(message, &self.me) {
+                    return;
+                }
+                let chat_id = message.chat.id;
+                if !self.is_allowed(chat_id, message.from.as_deref()) {
+                    let text = format!(
+                        "This bot does not have any information about you ({chat_id}) and therefore doesn't serve you. If you think this is a mistake you need to message the admins about this yourself. This usage attempt is not stored."
+                    );
+                    let send_message_params = SendMessageParams::builder()
+                        .chat_id(chat_id)
+                        .text(text)
+                        .build();
+                    self.bot
+                        .send_message(&send_message_params)
+                        .expect("Should be able to respond to non allowed users");
+                    return;
+                }
+                let message_id = message.message_id;
+                if let Some(position) = chats.push(chat_id, update) {
+                    self.send_queue_position(chat_id, message_id, position);
+                }
+            }
+            UpdateContent::CallbackQuery(ref query) => {
+                let run_in_chat = match (
+                    query.data.as_deref().and_then(Action::parse),
+                    &query.message,
+                ) {
+                    (Some(Action::Run(_)), Some(MaybeInaccessibleMessage::Message(message)))
+                        if self.is_allowed(message.chat.id, Some(&query.from)) =>
+                    {
+                        Some(message.chat.id)
                     }
-                    UpdateContent::Message(message) | UpdateContent::EditedMessage(message) => {
-                        let chat_id = message.chat.id;
-                        if !self.allowed_users.is_empty() && !self.allowed_users.contains(&chat_id)
-                        {
-                            let text = format!(
-                                "This bot does not have any information about you ({chat_id}) and therefore doesn't serve you. If you think this is a mistake you need to message the admins about this yourself. This usage attempt is not stored."
-                            );
-                            let send_message_params = SendMessageParams::builder()
-                                .chat_id(chat_id)
-                                .text(text)
-                                .build();
-                            self.bot
-                                .send_message(&send_message_params)
-                                .expect("Should be able to respond to non allowed users");
-                            continue;
-                        }
-
-                        if let Err(error) = self.analyze_message(&message, &inspect_url) {
-                            self.bot
-                                .send_message(
-                                    &SendMessageParams::builder()
+                    _ => None,
+                };
+                let Some(chat_id) = run_in_chat else {
+                    // Everything but running is quick and has to be answered before the query expires
+                    self.callback(query, inspect_url);
+                    return;
+                };
+                let query = query.as_ref().clone();
+                let position = chats.push(chat_id, update);
+                let text = position.map(|position| format!("Queued, position {position}"));
+                self.answer_callback(&query, text.as_deref());
+            }
+            UpdateContent::InlineQuery(query) => {
+                inline_queries.push(query.from.id, query);
+            }
+            _ => {} // Ignore
+        }
+    }
+
+    fn send_queue_position(&self, chat_id: i64, message_id: i32, position: usize) {
+        let sent = self.bot.send_message(
+            &SendMessageParams::builder()
+                .chat_id(chat_id)
+                .reply_parameters(
+                    ReplyParameters::builder()
+                        .chat_id(chat_id)
+                        .message_id(message_id)
+                        .build(),
+                )
+                .text(format!("Queued, position {position}"))
+                .build(),
+        );
+        if let Err(error) = sent {
+            eprintln!("Failed to send queue position: {error:#}");
+        }
+    }
+
+    /// Runs on a worker, the update was already checked by [`Self::receive`]
+    fn handle_update(&self, update: Update, inspect_url: InspectUrl) {
+        match update.content {
+            UpdateContent::Message(message) | UpdateContent::EditedMessage(message) => {
+                if let Err(error) = self.analyze_message(&message, inspect_url) {
+                    self.bot
+                        .send_message(
+                            &SendMessageParams::builder()
+                                .chat_id(message.chat.id)
+                                .reply_parameters(
+                                    ReplyParameters::builder()
                                         .chat_id(message.chat.id)
-                                        .reply_parameters(
-                                            ReplyParameters::builder()
-                                                .chat_id(message.chat.id)
-                                                .message_id(message.message_id)
-                                                .build(),
-                                        )
-                                        .text(format!("{error:?}"))
+                                        .message_id(message.message_id)
                                         .build(),
                                 )
-                                .expect(
-                                    "Should be able to send user error while analyzing the message",
-                                );
-                        }
-                    }
-                    _ => {} // Ignore
+                                .text(format!("{error:?}"))
+                                .build(),
+                        )
+                        .expect("Should be able to send user error while analyzing the message");
                 }
             }
+            UpdateContent::CallbackQuery(query) => self.callback(&query, inspect_url),
+            _ => {} // Ignore
+        }
+    }
+
+    fn callback(&self, query: &CallbackQuery, inspect_url: InspectUrl) {
+        if let Err(error) = self.handle_callback(query, inspect_url) {
+            eprintln!("Failed to handle callback query: {error:#}");
+            self.answer_callback(query, Some(&format!("{error:#}")));
         }
     }
 
@@ -136,21 +307,144 @@ impl Telegram {
         });
     }
 
-    fn analyze_message<F>(&self, message: &Message, inspect_url: &F) -> anyhow::Result<()>
-    where
-        F: Fn(&Bot, i64, &ReplyParameters, &str) -> anyhow::Result<()>,
-    {
-        let urls = get_text_urls(message)?;
+    fn analyze_message(&self, message: &Message, inspect_url: InspectUrl) -> anyhow::Result<()> {
+        let chat_id = message.chat.id;
+        let text = message
+            .text
+            .as_deref()
+            .or(message.caption.as_deref())
+            .unwrap_or_default();
+        let command = Command::parse(text).context("Unknown command, see /help")?;
+        let reply_params = ReplyParameters::builder()
+            .chat_id(chat_id)
+            .message_id(message.message_id)
+            .build();
+        match command {
+            Command::Help => send_text(&self.bot, chat_id, &reply_params, HELP),
+            Command::Default => {
+                let parts = self.default_parts(chat_id);
+                self.bot
+                    .send_message(
+                        &SendMessageParams::builder()
+                            .chat_id(chat_id)
+                            .reply_parameters(reply_params)
+                            .text(format!(
+                                "Preselected for links in this chat: {}",
+                                keyboard::describe(parts)
+                            ))
+                            .reply_markup(ReplyMarkup::InlineKeyboardMarkup(keyboard::markup(
+                                Purpose::Default,
+                                parts,
+                            )))
+                            .build(),
+                    )
+                    .context("Should be able to send default keyboard")?;
+                Ok(())
+            }
+            Command::Select => {
+                // In groups the bot is mentioned in a reply to the message with the url
+                let message = with_urls(message)?;
+                anyhow::ensure!(!get_urls(message)?.is_empty(), "No url found in message");
+                self.bot
+                    .send_message(
+                        &SendMessageParams::builder()
+                            .chat_id(chat_id)
+                            .reply_parameters(
+                                ReplyParameters::builder()
+                                    .chat_id(chat_id)
+                                    .message_id(message.message_id)
+                                    .build(),
+                            )
+                            .text("What should I inspect?")
+                            .reply_markup(ReplyMarkup::InlineKeyboardMarkup(keyboard::markup(
+                                Purpose::Inspect,
+                                self.default_parts(chat_id),
+                            )))
+                            .build(),
+                    )
+                    .context("Should be able to send keyboard")?;
+                Ok(())
+            }
+            Command::Inspect(parts) => {
+                self.inspect_message(with_urls(message)?, parts, false, inspect_url)
+            }
+            Command::Json => {
+                self.inspect_message(with_urls(message)?, Parts::DEFAULT, true, inspect_url)
+            }
+        }
+    }
+
+    fn inspect_message(
+        &self,
+        message: &Message,
+        parts: Parts,
+        json: bool,
+        inspect_url: InspectUrl,
+    ) -> anyhow::Result<()> {
+        let chat_id = message.chat.id;
+        let urls = get_urls(message)?;
         anyhow::ensure!(!urls.is_empty(), "No url found in message");
+        let text = message.text.as_ref().or(message.caption.as_ref());
         for url in urls {
+            println!("inspect_url {chat_id:>10}: {url}");
+            // Hyperlinks and link previews are not part of the text which is required for a quote
+            let quote = text.is_some_and(|text| text.contains(url)).then_some(url);
             let reply_params = ReplyParameters::builder()
-                .chat_id(message.chat.id)
+                .chat_id(chat_id)
                 .message_id(message.message_id)
-                .quote(url)
+                .maybe_quote(quote)
                 .build();
-            if let Err(error) = inspect_url(&self.bot, message.chat.id, &reply_params, url)
-                .context("Failed to inspect url")
-            {
+            let media_dir = tempfile::tempdir().context("Should be able to create tempdir")?;
+            let mut document = JsonDocument::new(url);
+            // Groups get a single summary instead of a message per section
+            let condensed = is_group(&message.chat);
+            let mut summary = String::new();
+            let mut final_uri = None;
+            // yt-dlp is the slowest part and can take minutes
+            let status_message = (parts.video || parts.audio)
+                .then(|| self.send_status(chat_id, &reply_params, "Start yt-dlp…"))
+                .flatten();
+            let mut yt_dlp_failed = None;
+            let result = inspect_url(url, parts, media_dir.path(), &mut |stage, report| {
+                if let Some(uri) = report.data.get("final_uri").and_then(|uri| uri.as_str()) {
+                    final_uri = Some(uri.to_owned());
+                }
+                if stage.starts_with("yt-dlp")
+                    && report.data.get("success") == Some(&serde_json::Value::Bool(false))
+                {
+                    yt_dlp_failed = Some(report.data.get("exit_code").cloned().unwrap_or_default());
+                }
+                if json {
+                    document.add(stage, &report);
+                    return Ok(());
+                }
+                let video_file_ids = if condensed {
+                    send_condensed(&self.bot, chat_id, &reply_params, &report, &mut summary)
+                } else {
+                    send_report(&self.bot, chat_id, &reply_params, &report)?
+                };
+                if let Some(file_id) = video_file_ids.into_iter().next() {
+                    let mut videos = self.videos.lock().expect("Should be able to lock videos");
+                    videos.insert(url.to_owned(), file_id.clone());
+                    if let Some(final_uri) = &final_uri {
+                        videos.insert(final_uri.clone(), file_id);
+                    }
+                }
+                Ok(())
+            })
+            .and_then(|()| {
+                if json {
+                    send_json_document(&self.bot, chat_id, &reply_params, &document)?;
+                }
+                if !summary.is_empty() {
+                    send_text(&self.bot, chat_id, &reply_params, summary.trim_end())?;
+                }
+                Ok(())
+            });
+            if let Some(message_id) = status_message {
+                self.finish_status(chat_id, message_id, yt_dlp_failed);
+            }
+            if let Err(error) = result.context("Failed to inspect url") {
                 self.bot
                     .send_message(
                         &SendMessageParams::builder()
@@ -164,28 +458,314 @@ impl Telegram {
         }
         Ok(())
     }
+
+    /// Status shown while the inspection runs. Failing to send it does not stop the inspection.
+    fn send_status(&self, chat_id: i64, reply_params: &ReplyParameters, text: &str) -> Option<i32> {
+        let sent = self.bot.send_message(
+            &SendMessageParams::builder()
+                .chat_id(chat_id)
+                .reply_parameters(reply_params.clone())
+                .text(text)
+                .build(),
+        );
+        match sent {
+            Ok(sent) => Some(sent.result.message_id),
+            Err(error) => {
+                eprintln!("Failed to send status message: {error:#}");
+                None
+            }
+        }
+    }
+
+    /// Remove the status once yt-dlp is done or keep it with the exit code when yt-dlp failed
+    fn finish_status(&self, chat_id: i64, message_id: i32, failed: Option<serde_json::Value>) {
+        let result = failed.map_or_else(
+            || {
+                self.bot
+                    .delete_message(
+                        &DeleteMessageParams::builder()
+                            .chat_id(chat_id)
+                            .message_id(message_id)
+                            .build(),
+                    )
+                    .map(drop)
+            },
+            |exit_code| {
+                self.bot
+                    .edit_message_text(
+                        &EditMessageTextParams::builder()
+                            .chat_id(chat_id)
+                            .message_id(message_id)
+                            .text(format!("yt-dlp failed with exit code {exit_code}"))
+                            .build(),
+                    )
+                    .map(drop)
+            },
+        );
+        if let Err(error) = result {
+            eprintln!("Failed to finish status message: {error:#}");
+        }
+    }
+
+    /// Handle a button press of a [`keyboard`]
+    fn handle_callback(
+        &self,
+        query: &CallbackQuery,
+        inspect_url: InspectUrl,
+    ) -> anyhow::Result<()> {
+        let action = query
+            .data
+            .as_deref()
+            .and_then(Action::parse)
+            .context("Should be a known button")?;
+        let Some(MaybeInaccessibleMessage::Message(message)) = &query.message else {
+            self.answer_callback(query, Some("This message is too old"));
+            return Ok(());
+        };
+        let chat_id = message.chat.id;
+        if !self.is_allowed(chat_id, Some(&query.from)) {
+            self.answer_callback(query, Some("You are not allowed to use this bot"));
+            return Ok(());
+        }
+
+        match action {
+            Action::Show(purpose, parts) => {
+                self.answer_callback(query, None);
+                let text = match purpose {
+                    Purpose::Inspect => "What should I inspect?".to_owned(),
+                    Purpose::Default => format!(
+                        "Preselected for links in this chat: {}",
+                        keyboard::describe(parts)
+                    ),
+                };
+                self.edit_keyboard_message(message, text, Some(keyboard::markup(purpose, parts)))?;
+            }
+            Action::SaveDefault(purpose, parts) => {
+                self.defaults
+                    .lock()
+                    .expect("Should be able to lock defaults")
+                    .insert(chat_id, parts);
+                let text = format!(
+                    "Preselected for links in this chat: {}",
+                    keyboard::describe(parts)
+                );
+                self.answer_callback(query, Some(&text));
+                if purpose == Purpose::Default {
+                    // The keyboard of /default is done, the one for links is still needed to run
+                    self.edit_keyboard_message(message, text, None)?;
+                }
+            }
+            Action::Run(parts) => {
+                // Already answered when it was queued
+                let original = message
+                    .reply_to_message
+                    .as_deref()
+                    .context("Should have the message with the links")?;
+                let text = format!("Inspecting {}", keyboard::describe(parts));
+                self.edit_keyboard_message(message, text, None)?;
+                self.inspect_message(original, parts, false, inspect_url)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn answer_inline_query(&self, query: &InlineQuery) -> anyhow::Result<()> {
+        let user_id = i64::try_from(query.from.id).context("Should be a valid user id")?;
+        let results = if self.is_allowed(user_id, Some(&query.from)) {
+            inline_query::parse_url(&query.query).map_or_else(Vec::new, |uri| {
+                println!("inline_query {user_id:>10}: {uri}");
+                let final_uri = crate::http::final_uri(&uri.to_string())
+                    .map_err(|error| eprintln!("Failed to resolve inline query url: {error:#}"))
+                    .ok();
+                let videos = self.videos.lock().expect("Should be able to lock videos");
+                let video_file_id = [Some(&uri), final_uri.as_ref()]
+                    .into_iter()
+                    .flatten()
+                    .find_map(|uri| videos.get(&uri.to_string()));
+                inline_query::results(&uri, final_uri.as_ref(), video_file_id.map(String::as_str))
+            })
+        } else {
+            Vec::new()
+        };
+        self.bot
+            .answer_inline_query(
+                &AnswerInlineQueryParams::builder()
+                    .inline_query_id(query.id.clone())
+                    .results(results)
+                    .is_personal(true)
+                    .cache_time(0)
+                    .build(),
+            )
+            .context("Should be able to answer_inline_query")?;
+        Ok(())
+    }
+
+    fn answer_callback(&self, query: &CallbackQuery, text: Option<&str>) {
+        let answered = self.bot.answer_callback_query(
+            &AnswerCallbackQueryParams::builder()
+                .callback_query_id(query.id.clone())
+                .maybe_text(text)
+                .build(),
+        );
+        if let Err(error) = answered {
+            eprintln!("Failed to answer callback query: {error}");
+        }
+    }
+
+    fn edit_keyboard_message(
+        &self,
+        message: &Message,
+        text: String,
+        keyboard: Option<InlineKeyboardMarkup>,
+    ) -> anyhow::Result<()> {
+        self.bot
+            .edit_message_text(
+                &EditMessageTextParams::builder()
+                    .chat_id(message.chat.id)
+                    .message_id(message.message_id)
+                    .text(text)
+                    .maybe_reply_markup(keyboard)
+                    .build(),
+            )
+            .context("Should be able to edit keyboard message")?;
+        Ok(())
+    }
+
+    fn default_parts(&self, chat_id: i64) -> Parts {
+        self.defaults
+            .lock()
+            .expect("Should be able to lock defaults")
+            .get(&chat_id)
+            .copied()
+            .unwrap_or(Parts::DEFAULT)
+    }
+
+    /// Either the whole chat or the user is allowed
+    fn is_allowed(&self, chat_id: i64, user: Option<&User>) -> bool {
+        self.allowed_users.is_empty()
+            || self.allowed_users.contains(&chat_id)
+            || user
+                .and_then(|user| i64::try_from(user.id).ok())
+                .is_some_and(|user_id| self.allowed_users.contains(&user_id))
+    }
 }
 
-fn get_text_urls(message: &Message) -> anyhow::Result<Vec<&str>> {
-    let (Some(text), Some(entities)) = (&message.text, &message.entities) else {
-        return Ok(Vec::new());
+/// In groups only commands, mentions and replies to the bot are meant for it
+fn is_addressed(message: &Message, me: &User) -> bool {
+    if !is_group(&message.chat) {
+        return true;
+    }
+    let username = me.username.as_deref().unwrap_or_default();
+    let (text, entities) = if message.text.is_some() {
+        (&message.text, &message.entities)
+    } else {
+        (&message.caption, &message.caption_entities)
     };
-    let entities = entities
+    let text = text.as_deref().unwrap_or_default();
+
+    if let Some(command) = text.strip_prefix('/') {
+        let command = command.split_whitespace().next().unwrap_or_default();
+        // Commands for other bots look like /help@other_bot or are unknown like /ban
+        return command.split_once('@').map_or_else(
+            || Command::parse(text).is_some(),
+            |(_, target)| target.eq_ignore_ascii_case(username),
+        );
+    }
+
+    let mentioned = entities
         .iter()
-        .filter(|entity| matches!(entity.type_field, MessageEntityType::Url));
+        .flatten()
+        .any(|entity| match entity.type_field {
+            MessageEntityType::Mention => utf16_slice(text, entity.offset, entity.length)
+                .and_then(|mention| mention.strip_prefix('@'))
+                .is_some_and(|mention| mention.eq_ignore_ascii_case(username)),
+            MessageEntityType::TextMention => {
+                entity.user.as_ref().is_some_and(|user| user.id == me.id)
+            }
+            _ => false,
+        });
+    let reply_to_bot = message
+        .reply_to_message
+        .as_ref()
+        .and_then(|reply| reply.from.as_ref())
+        .is_some_and(|from| from.id == me.id);
+    mentioned || reply_to_bot
+}
+
+/// The message itself or the replied to message when a bare command was used
+fn with_urls(message: &Message) -> anyhow::Result<&Message> {
+    if get_urls(message)?.is_empty()
+        && let Some(reply) = &message.reply_to_message
+    {
+        return Ok(reply);
+    }
+    Ok(message)
+}
+
+/// Urls in the text, caption, hyperlinks and link preview of the message without duplicates
+fn get_urls(message: &Message) -> anyhow::Result<Vec<&str>> {
     let mut urls = Vec::new();
-    for entity in entities {
-        let start = entity.offset as usize;
-        let length = entity.length as usize;
-        let end = start.saturating_add(length);
-        let url = text
-            .get(start..end)
-            .with_context(|| format!("There should be an url at the given entity: {entity:?}"))?;
-        urls.push(url);
+    let texts = [
+        (&message.text, &message.entities),
+        (&message.caption, &message.caption_entities),
+    ];
+    for (text, entities) in texts {
+        let (Some(text), Some(entities)) = (text, entities) else {
+            continue;
+        };
+        for entity in entities {
+            match entity.type_field {
+                MessageEntityType::Url => {
+                    let url =
+                        utf16_slice(text, entity.offset, entity.length).with_context(|| {
+                            format!("There should be an url at the given entity: {entity:?}")
+                        })?;
+                    urls.push(url);
+                }
+                MessageEntityType::TextLink => urls.extend(entity.url.as_deref()),
+                _ => {}
+            }
+        }
+    }
+    // Forwarded messages might only have the link in their preview
+    urls.extend(
+        message
+            .link_preview_options
+            .as_ref()
+            .and_then(|options| options.url.as_deref()),
+    );
+    let mut unique = Vec::with_capacity(urls.len());
+    for url in urls {
+        if !unique.contains(&url) {
+            unique.push(url);
+        }
     }
-    Ok(urls)
+    Ok(unique)
 }
 
+/// Entity offsets and lengths are in UTF-16 code units
+fn utf16_slice(text: &str, offset: u16, length: u16) -> Option<&str> {
+    let mut start = None;
+    let mut units = 0;
+    for (index, character) in text.char_indices() {
+        if units == usize::from(offset) {
+            start = Some(index);
+        }
+        if units == usize::from(offset) + usize::from(length) {
+            return text.get(start?..index);
+        }
+        units += character.len_utf16();
+    }
+    if units == usize::from(offset) + usize::from(length) {
+        return text.get(start.unwrap_or(text.len())..);
+    }
+    None
+}
+
+/// Send the code as preformatted message.
+///
+/// Long code is split over multiple messages or sent as a document when it would be too many.
+/// JSON and logs are hard to read when split so they are sent as a document as soon as they do not fit a single message.
 #[expect(clippy::cast_possible_truncation)]
 pub fn send_code(
     bot: &Bot,
@@ -195,25 +775,579 @@ pub fn send_code(
     language: Option<&str>,
     code: &str,
 ) -> anyhow::Result<()> {
-    let text = header.map_or_else(|| code.to_owned(), |header| format!("{header}:\n{code}"));
-    let offset = header.map_or(0, |header| {
-        header.encode_utf16().count().saturating_add(":\n".len()) as u16
-    });
-    let entity = MessageEntity::builder()
-        .type_field(MessageEntityType::Pre)
-        .maybe_language(language)
-        .offset(offset)
-        .length(code.encode_utf16().count() as u16)
-        .build();
-    bot.send_message(
-        &SendMessageParams::builder()
-            .link_preview_options(frankenstein::types::LinkPreviewOptions::DISABLED)
+    let header_length = header.map_or(0, |header| header.encode_utf16().count());
+    let chunks = crate::split::split(
+        code,
+        MESSAGE_LIMIT.saturating_sub(header_length + CODE_HEADER_RESERVE),
+    );
+    let max_chunks = if matches!(language, Some("json" | "log")) {
+        1
+    } else {
+        *MAX_CHUNKS
+    };
+    if chunks.len() > max_chunks {
+        let filename = document_name(header, language);
+        let caption = header.map(|header| format!("{header} ({} lines)", code.lines().count()));
+        return send_document(
+            bot,
+            chat_id,
+            reply_params,
+            &filename,
+            code,
+            caption.as_deref(),
+        );
+    }
+
+    let total = chunks.len();
+    for (index, chunk) in chunks.into_iter().enumerate() {
+        let prefix = match header {
+            Some(header) if total > 1 => format!("{header} ({}/{total}):\n", index + 1),
+            Some(header) => format!("{header}:\n"),
+            None => String::new(),
+        };
+        let entity = MessageEntity::builder()
+            .type_field(MessageEntityType::Pre)
+            .maybe_language(language)
+            .offset(prefix.encode_utf16().count() as u16)
+            .length(chunk.encode_utf16().count() as u16)
+            .build();
+        bot.send_message(
+            &SendMessageParams::builder()
+                .link_preview_options(LinkPreviewOptions::DISABLED)
+                .chat_id(chat_id)
+                .reply_parameters(reply_params.clone())
+                .entities(vec![entity])
+                .text(prefix + chunk)
+                .build(),
+        )
+        .context("Should be able to send_message")?;
+    }
+    Ok(())
+}
+
+/// Filename like `stats.json` or `yt-dlp-stderr.log` for code which is sent as a document
+fn document_name(header: Option<&str>, language: Option<&str>) -> String {
+    let name = header
+        .unwrap_or("code")
+        .chars()
+        .map(|character| {
+            if character.is_alphanumeric() || matches!(character, '.' | '-' | '_') {
+                character
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let extension = match language {
+        Some("json") => "json",
+        Some("log") => "log",
+        _ => "txt",
+    };
+    format!("{name}.{extension}")
+}
+
+/// Send the document as code message or as file when it is too long for a message
+fn send_json_document(
+    bot: &Bot,
+    chat_id: i64,
+    reply_params: &ReplyParameters,
+    document: &JsonDocument,
+) -> anyhow::Result<()> {
+    let json = document.to_string_pretty();
+    if json.encode_utf16().count() <= MESSAGE_LIMIT {
+        send_code(bot, chat_id, reply_params, None, Some("json"), &json)
+    } else {
+        send_document(bot, chat_id, reply_params, "inspection.json", &json, None)
+    }
+}
+
+/// Upload the content as a file with the given name
+pub fn send_document(
+    bot: &Bot,
+    chat_id: i64,
+    reply_params: &ReplyParameters,
+    filename: &str,
+    content: &str,
+    caption: Option<&str>,
+) -> anyhow::Result<()> {
+    let dir = tempfile::tempdir().context("Should be able to create tempdir")?;
+    let path = dir.path().join(filename);
+    std::fs::write(&path, content).context("Should be able to write document")?;
+    bot.send_document(
+        &SendDocumentParams::builder()
             .chat_id(chat_id)
             .reply_parameters(reply_params.clone())
-            .entities(vec![entity])
-            .text(text)
+            .document(path)
+            .maybe_caption(caption)
             .build(),
     )
-    .context("Should be able to send_message")?;
+    .context("Should be able to send_document")?;
+    Ok(())
+}
+
+/// Send every section of the report as its own message.
+///
+/// Failing to send a section is reported to the user and the remaining sections are still sent.
+/// Returns the file ids of the sent videos which can be sent again without uploading them.
+pub fn send_report(
+    bot: &Bot,
+    chat_id: i64,
+    reply_params: &ReplyParameters,
+    report: &Report,
+) -> anyhow::Result<Vec<String>> {
+    let mut video_file_ids = Vec::new();
+    for section in &report.sections {
+        match send_section(bot, chat_id, reply_params, section) {
+            Ok(file_id) => video_file_ids.extend(file_id),
+            Err(error) => {
+                let text = format!("Failed to send {}: {error:#}", section_kind(section));
+                send_text(bot, chat_id, reply_params, &text)
+                    .context("Should be able to send section error to user")?;
+            }
+        }
+    }
+    Ok(video_file_ids)
+}
+
+/// Send media and documents on their own and collect everything else into the summary
+fn send_condensed(
+    bot: &Bot,
+    chat_id: i64,
+    reply_params: &ReplyParameters,
+    report: &Report,
+    summary: &mut String,
+) -> Vec<String> {
+    let mut video_file_ids = Vec::new();
+    for section in condense(report, summary) {
+        match send_section(bot, chat_id, reply_params, section) {
+            Ok(file_id) => video_file_ids.extend(file_id),
+            Err(error) => {
+                writeln!(
+                    summary,
+                    "Failed to send {}: {error:#}\n",
+                    section_kind(section)
+                )
+                .unwrap();
+            }
+        }
+    }
+    video_file_ids
+}
+
+/// Append the textual sections to the summary and return the ones which need their own message.
+///
+/// Logs and JSON are left out, they are still available with /json.
+fn condense<'report>(report: &'report Report, summary: &mut String) -> Vec<&'report Section> {
+    let mut own_message = Vec::new();
+    for section in &report.sections {
+        match section {
+            Section::Code {
+                language: Some("log"),
+                ..
+            }
+            | Section::Json { .. } => {}
+            Section::Text { header, text }
+            | Section::Code {
+                header, code: text, ..
+            } => {
+                if let Some(header) = header {
+                    writeln!(summary, "{header}:").unwrap();
+                }
+                writeln!(summary, "{}\n", text.trim()).unwrap();
+            }
+            Section::Table { header, rows } => {
+                if let Some(header) = header {
+                    writeln!(summary, "{header}").unwrap();
+                }
+                for (key, value) in rows {
+                    writeln!(summary, "{key}: {value}").unwrap();
+                }
+                summary.push('\n');
+            }
+            Section::Error(error) => writeln!(summary, "Error: {error}\n").unwrap(),
+            Section::Document { .. }
+            | Section::Photo { .. }
+            | Section::PhotoGroup(_)
+            | Section::Audio(_)
+            | Section::AudioFile(_)
+            | Section::Video(_)
+            | Section::TelegramPost { .. } => own_message.push(section),
+        }
+    }
+    own_message
+}
+
+const fn is_group(chat: &Chat) -> bool {
+    matches!(chat.type_field, ChatType::Group | ChatType::Supergroup)
+}
+
+const fn section_kind(section: &Section) -> &'static str {
+    match section {
+        Section::Text { .. } => "text",
+        Section::Code { .. } => "code",
+        Section::Json { .. } => "json",
+        Section::Table { .. } => "table",
+        Section::Photo { .. } => "photo",
+        Section::PhotoGroup(_) => "photos",
+        Section::Audio(_) | Section::AudioFile(_) => "audio",
+        Section::Video(_) => "video",
+        Section::Document { .. } => "document",
+        Section::TelegramPost { .. } => "telegram post",
+        Section::Error(_) => "error",
+    }
+}
+
+/// Returns the file id when a video was sent
+fn send_section(
+    bot: &Bot,
+    chat_id: i64,
+    reply_params: &ReplyParameters,
+    section: &Section,
+) -> anyhow::Result<Option<String>> {
+    match section {
+        Section::Text { header, text } => {
+            let text = header
+                .as_ref()
+                .map_or_else(|| text.clone(), |header| format!("{header}:\n\n{text}"));
+            send_text(bot, chat_id, reply_params, &text)?;
+        }
+        Section::Code {
+            header,
+            language,
+            code,
+        } => send_code(
+            bot,
+            chat_id,
+            reply_params,
+            header.as_deref(),
+            *language,
+            code,
+        )?,
+        Section::Json { header, value } => {
+            let json = serde_json::to_string_pretty(value)?;
+            send_code(
+                bot,
+                chat_id,
+                reply_params,
+                Some(header),
+                Some("json"),
+                &json,
+            )?;
+        }
+        Section::Table { header, rows } => {
+            let mut code = String::new();
+            for (key, value) in rows {
+                writeln!(code, "{key}: {value}").unwrap();
+            }
+            send_code(bot, chat_id, reply_params, header.as_deref(), None, &code)?;
+        }
+        Section::Photo { url, caption } => {
+            bot.send_photo(
+                &SendPhotoParams::builder()
+                    .chat_id(chat_id)
+                    .reply_parameters(reply_params.clone())
+                    .photo(url.clone())
+                    .maybe_caption(caption.as_deref().map(photo_caption))
+                    .build(),
+            )?;
+        }
+        Section::PhotoGroup(photos) => send_photo_group(bot, chat_id, reply_params, photos)?,
+        Section::Audio(audio) => {
+            bot.send_audio(
+                &SendAudioParams::builder()
+                    .chat_id(chat_id)
+                    .reply_parameters(reply_params.clone())
+                    .audio(audio.url.clone())
+                    .maybe_duration(audio.duration)
+                    .maybe_performer(audio.performer.clone())
+                    .maybe_title(audio.title.clone())
+                    .maybe_caption(audio.caption.clone())
+                    .build(),
+            )?;
+        }
+        Section::AudioFile(path) => send_audio_file(bot, chat_id, reply_params, path)?,
+        Section::Video(video) => return send_video(bot, chat_id, reply_params, video),
+        Section::Document {
+            filename,
+            caption,
+            content,
+        } => send_document(
+            bot,
+            chat_id,
+            reply_params,
+            filename,
+            content,
+            caption.as_deref(),
+        )?,
+        Section::TelegramPost { chat, message_id } => {
+            // Only works when the bot has access to the chat, the preview is already sent anyway
+            let forwarded = bot.forward_message(
+                &ForwardMessageParams::builder()
+                    .chat_id(chat_id)
+                    .from_chat_id(chat.clone())
+                    .message_id(*message_id)
+                    .build(),
+            );
+            if let Err(error) = forwarded {
+                eprintln!("Failed to forward {chat}/{message_id}: {error}");
+            }
+        }
+        Section::Error(error) => send_text(bot, chat_id, reply_params, error)?,
+    }
+    Ok(None)
+}
+
+fn send_audio_file(
+    bot: &Bot,
+    chat_id: i64,
+    reply_params: &ReplyParameters,
+    path: &Path,
+) -> anyhow::Result<()> {
+    bot.send_chat_action(
+        &SendChatActionParams::builder()
+            .chat_id(chat_id)
+            .action(ChatAction::UploadVoice)
+            .build(),
+    )?;
+    bot.send_audio(
+        &SendAudioParams::builder()
+            .chat_id(chat_id)
+            .reply_parameters(reply_params.clone())
+            .audio(path.to_path_buf())
+            .build(),
+    )?;
     Ok(())
 }
+
+fn send_video(
+    bot: &Bot,
+    chat_id: i64,
+    reply_params: &ReplyParameters,
+    video: &Video,
+) -> anyhow::Result<Option<String>> {
+    bot.send_chat_action(
+        &SendChatActionParams::builder()
+            .chat_id(chat_id)
+            .action(ChatAction::UploadVideo)
+            .build(),
+    )?;
+    let sent = bot.send_video(
+        &SendVideoParams::builder()
+            .chat_id(chat_id)
+            .reply_parameters(reply_params.clone())
+            .width(video.width)
+            .height(video.height)
+            .duration(video.duration)
+            .video(video.path.clone())
+            .build(),
+    )?;
+    Ok(sent.result.video.map(|video| video.file_id))
+}
+
+/// Alt texts can be longer than Telegram accepts as a caption
+pub fn photo_caption(caption: &str) -> String {
+    crate::split::truncate(caption, CAPTION_LIMIT)
+}
+
+fn send_photo_group(
+    bot: &Bot,
+    chat_id: i64,
+    reply_params: &ReplyParameters,
+    photos: &[Photo],
+) -> anyhow::Result<()> {
+    // Telegram allows 2-10 items per media group
+    for chunk in photos.chunks(10) {
+        if let [photo] = chunk {
+            bot.send_photo(
+                &SendPhotoParams::builder()
+                    .chat_id(chat_id)
+                    .reply_parameters(reply_params.clone())
+                    .photo(photo.url.clone())
+                    .maybe_caption(photo.caption.as_deref().map(photo_caption))
+                    .build(),
+            )?;
+            continue;
+        }
+        let media = chunk
+            .iter()
+            .map(|photo| {
+                MediaGroupInputMedia::Photo(
+                    InputMediaPhoto::builder()
+                        .media(photo.url.clone())
+                        .maybe_caption(photo.caption.as_deref().map(photo_caption))
+                        .build(),
+                )
+            })
+            .collect();
+        bot.send_media_group(
+            &SendMediaGroupParams::builder()
+                .chat_id(chat_id)
+                .reply_parameters(reply_params.clone())
+                .media(media)
+                .build(),
+        )?;
+    }
+    Ok(())
+}
+
+fn send_text(
+    bot: &Bot,
+    chat_id: i64,
+    reply_params: &ReplyParameters,
+    text: &str,
+) -> anyhow::Result<()> {
+    let chunks = crate::split::split(text, MESSAGE_LIMIT);
+    if chunks.len() > *MAX_CHUNKS {
+        return send_document(bot, chat_id, reply_params, "text.txt", text, None);
+    }
+    for chunk in chunks {
+        bot.send_message(
+            &SendMessageParams::builder()
+                .link_preview_options(LinkPreviewOptions::DISABLED)
+                .chat_id(chat_id)
+                .reply_parameters(reply_params.clone())
+                .text(chunk)
+                .build(),
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn document_name_works() {
+    assert_eq!(document_name(Some("stats"), Some("json")), "stats.json");
+    assert_eq!(
+        document_name(Some("video.subtitleInfos"), Some("json")),
+        "video.subtitleInfos.json"
+    );
+    assert_eq!(
+        document_name(Some("yt-dlp stderr"), Some("log")),
+        "yt-dlp-stderr.log"
+    );
+    assert_eq!(document_name(None, None), "code.txt");
+}
+
+#[test]
+fn get_urls_from_all_sources() {
+    let message = serde_json::from_value::<Message>(serde_json::json!({
+        "message_id": 1,
+        "date": 0,
+        "chat": {"id": 1, "type": "private"},
+        "text": "Größe https://example.com/a and hyperlink and https://example.com/a again",
+        "entities": [
+            {"type": "url", "offset": 6, "length": 21},
+            {"type": "text_link", "offset": 32, "length": 9, "url": "https://example.com/b"},
+            {"type": "url", "offset": 46, "length": 21},
+        ],
+        "link_preview_options": {"url": "https://example.com/c"},
+    }))
+    .unwrap();
+    assert_eq!(
+        get_urls(&message).unwrap(),
+        [
+            "https://example.com/a",
+            "https://example.com/b",
+            "https://example.com/c"
+        ]
+    );
+
+    let message = serde_json::from_value::<Message>(serde_json::json!({
+        "message_id": 2,
+        "date": 0,
+        "chat": {"id": 1, "type": "private"},
+        "caption": "🦀 https://example.com/d",
+        "caption_entities": [{"type": "url", "offset": 3, "length": 21}],
+    }))
+    .unwrap();
+    assert_eq!(get_urls(&message).unwrap(), ["https://example.com/d"]);
+}
+
+#[test]
+fn is_addressed_in_groups() {
+    let me = serde_json::from_value::<User>(serde_json::json!({
+        "id": 42, "is_bot": true, "first_name": "Quickscrape", "username": "QuickscrapeBot",
+    }))
+    .unwrap();
+    let message = |chat_type: &str, extra: serde_json::Value| {
+        let mut message = serde_json::json!({
+            "message_id": 1,
+            "date": 0,
+            "chat": {"id": -1, "type": chat_type},
+        });
+        message
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value::<Message>(message).unwrap()
+    };
+
+    let plain = serde_json::json!({"text": "https://example.com"});
+    assert!(is_addressed(&message("private", plain.clone()), &me));
+    assert!(!is_addressed(&message("supergroup", plain), &me));
+
+    let command = serde_json::json!({"text": "/meta https://example.com"});
+    assert!(is_addressed(&message("group", command), &me));
+    let own_command = serde_json::json!({"text": "/meta@quickscrapebot https://example.com"});
+    assert!(is_addressed(&message("group", own_command), &me));
+    let other_command = serde_json::json!({"text": "/meta@other_bot https://example.com"});
+    assert!(!is_addressed(&message("group", other_command), &me));
+    let unknown_command = serde_json::json!({"text": "/ban @spammer"});
+    assert!(!is_addressed(&message("group", unknown_command), &me));
+    let own_unknown_command = serde_json::json!({"text": "/ban@QuickscrapeBot @spammer"});
+    assert!(is_addressed(&message("group", own_unknown_command), &me));
+
+    let mention = serde_json::json!({
+        "text": "@QuickscrapeBot",
+        "entities": [{"type": "mention", "offset": 0, "length": 15}],
+    });
+    assert!(is_addressed(&message("group", mention), &me));
+    let other_mention = serde_json::json!({
+        "text": "@someone",
+        "entities": [{"type": "mention", "offset": 0, "length": 8}],
+    });
+    assert!(!is_addressed(&message("group", other_mention), &me));
+
+    let reply = serde_json::json!({
+        "text": "and this?",
+        "reply_to_message": {
+            "message_id": 0,
+            "date": 0,
+            "chat": {"id": -1, "type": "group"},
+            "from": {"id": 42, "is_bot": true, "first_name": "Quickscrape"},
+        },
+    });
+    assert!(is_addressed(&message("group", reply), &me));
+}
+
+#[test]
+fn raw_in_groups_is_sent_as_document() {
+    use crate::test_server::{Route, TestServer};
+
+    let url = TestServer::new().serve(vec![Route::new(
+        "/page",
+        "text/html",
+        "<html><body>Hello</body></html>",
+    )]);
+    let Some(Command::Inspect(parts)) = Command::parse(&format!("/raw {url}/page")) else {
+        panic!("should be inspect");
+    };
+    let media_dir = tempfile::tempdir().unwrap();
+    let mut summary = String::new();
+    let mut filenames = Vec::new();
+    crate::inspect_url(
+        &format!("{url}/page"),
+        parts,
+        media_dir.path(),
+        &mut |_, report| {
+            for section in condense(&report, &mut summary) {
+                if let Section::Document { filename, .. } = section {
+                    filenames.push(filename.clone());
+                }
+            }
+            Ok(())
+        },
+    )
+    .unwrap();
+    assert_eq!(filenames, ["body.html"]);
+    assert!(summary.is_empty());
+}
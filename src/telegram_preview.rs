@@ -0,0 +1,210 @@
+use std::fmt::Write as _;
+
+use anyhow::Context as _;
+use chrono::DateTime;
+use lazy_regex::regex_captures;
+use scraper::{ElementRef, Html};
+
+use crate::analyzer::{Analyzer, Page, host_is};
+use crate::html::html_to_text;
+use crate::macros::selector;
+use crate::report::{Photo, Report, Section};
+
+/// Public channel posts like `t.me/<channel>/<id>` via the `t.me/s/<channel>` web preview
+pub struct TelegramPreview;
+
+impl Analyzer for TelegramPreview {
+    fn name(&self) -> &'static str {
+        "t.me"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        (host_is(page.uri, "t.me") || host_is(page.uri, "telegram.me"))
+            && post_path(page.uri.path()).is_some()
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        let (channel, id) = post_path(page.uri.path()).expect("matches ensures a post path");
+        let mut response = crate::http::get(&format!("https://t.me/s/{channel}/{id}"))
+            .context("HTTP GET request of the web preview failed")?;
+        let body = response
+            .body_mut()
+            .read_to_string()
+            .context("Web preview should be a string")?;
+        analyze(&body, channel, id)
+    }
+}
+
+/// Channel and message id of `/<channel>/<id>` or `/s/<channel>/<id>`
+fn post_path(path: &str) -> Option<(&str, i32)> {
+    let path = path.strip_prefix("/s/").unwrap_or(path);
+    let (_, channel, id) = regex_captures!(r"^/?([A-Za-z][A-Za-z0-9_]{3,})/(\d+)/?$", path)?;
+    Some((channel, id.parse().ok()?))
+}
+
+fn analyze(html: &str, channel: &str, id: i32) -> anyhow::Result<Report> {
+    let html = Html::parse_document(html);
+    let mut report = Report::default();
+
+    let (channel_name, subscribers) = channel_info(&html);
+
+    let post_id = format!("{channel}/{id}");
+    let message = html
+        .select(selector!(".tgme_widget_message[data-post]"))
+        .find(|element| {
+            element
+                .attr("data-post")
+                .is_some_and(|post| post.eq_ignore_ascii_case(&post_id))
+        })
+        .context("The web preview should contain the post")?;
+
+    let text = message
+        .select(selector!(".tgme_widget_message_text"))
+        .next()
+        .map(|element| html_to_text(&element.inner_html()));
+    let views = message
+        .select(selector!(".tgme_widget_message_views"))
+        .next()
+        .map(|element| element_text(element));
+    let datetime = message
+        .select(selector!(".tgme_widget_message_date time[datetime]"))
+        .next()
+        .and_then(|element| element.attr("datetime"));
+    let date = datetime
+        .and_then(|datetime| DateTime::parse_from_rfc3339(datetime).ok())
+        .map(|datetime| datetime.to_utc());
+    let photos = message
+        .select(selector!(".tgme_widget_message_photo_wrap[style]"))
+        .filter_map(|element| {
+            let (_, url) = regex_captures!(
+                r#"background-image:url\(['"]?([^'")]+)"#,
+                element.attr("style")?
+            )?;
+            Some(url.to_owned())
+        })
+        .collect::<Vec<_>>();
+    let videos = message
+        .select(selector!(".tgme_widget_message_video_player video[src]"))
+        .filter_map(|element| element.attr("src"))
+        .map(ToOwned::to_owned)
+        .collect::<Vec<_>>();
+    let documents = message
+        .select(selector!(".tgme_widget_message_document_title"))
+        .map(|element| element_text(element))
+        .collect::<Vec<_>>();
+
+    let mut info = String::new();
+    if let Some(name) = &channel_name {
+        writeln!(info, "channel: {name} (@{channel})").unwrap();
+    }
+    if let Some(subscribers) = &subscribers {
+        writeln!(info, "subscribers: {subscribers}").unwrap();
+    }
+    if let Some(date) = date {
+        writeln!(info, "date: {date}").unwrap();
+    }
+    if let Some(views) = &views {
+        writeln!(info, "views: {views}").unwrap();
+    }
+    for video in &videos {
+        writeln!(info, "video: {video}").unwrap();
+    }
+    for document in &documents {
+        writeln!(info, "document: {document}").unwrap();
+    }
+    report.text(None, info);
+
+    if let Some(text) = &text {
+        report.text(Some("text"), text.clone());
+    }
+    report.photos(
+        photos
+            .iter()
+            .map(|url| Photo {
+                url: url.clone(),
+                caption: None,
+            })
+            .collect(),
+    );
+    report.push(Section::TelegramPost {
+        chat: format!("@{channel}"),
+        message_id: id,
+    });
+
+    report.insert_data("channel", channel);
+    report.insert_data("message_id", id);
+    report.insert_data("channel_name", channel_name);
+    report.insert_data("subscribers", subscribers);
+    report.insert_data("text", text);
+    report.insert_data("views", views);
+    report.insert_data("date", datetime);
+    report.insert_data("photos", photos);
+    report.insert_data("videos", videos);
+    report.insert_data("documents", documents);
+
+    Ok(report)
+}
+
+/// Name and subscriber count of the channel
+fn channel_info(html: &Html) -> (Option<String>, Option<String>) {
+    let channel_name = html
+        .select(selector!(
+            ".tgme_channel_info_header_title, .tgme_header_title"
+        ))
+        .next()
+        .map(|element| element_text(element));
+    let subscribers = html
+        .select(selector!(".tgme_channel_info_counter"))
+        .find(|counter| {
+            counter
+                .select(selector!(".counter_type"))
+                .any(|kind| element_text(kind) == "subscribers")
+        })
+        .and_then(|counter| counter.select(selector!(".counter_value")).next())
+        .map(|value| element_text(value))
+        .or_else(|| {
+            html.select(selector!(".tgme_header_counter"))
+                .next()
+                .map(|element| element_text(element).replace(" subscribers", ""))
+        });
+    (channel_name, subscribers)
+}
+
+fn element_text(element: ElementRef) -> String {
+    element.text().collect::<String>().trim().to_owned()
+}
+
+#[test]
+fn post_path_works() {
+    assert_eq!(post_path("/rustnews/421"), Some(("rustnews", 421)));
+    assert_eq!(post_path("/s/rustnews/421"), Some(("rustnews", 421)));
+    assert_eq!(post_path("/rustnews"), None);
+    assert_eq!(post_path("/joinchat/AbCdEf"), None);
+    assert_eq!(post_path("/c/1234567/89"), None);
+}
+
+#[test]
+fn fixture_report() {
+    let html = include_str!("../test/telegram-s-rustnews-421.html");
+    let report = analyze(html, "rustnews", 421).unwrap();
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: None, text }
+            if text == "channel: Rust News (@rustnews)\nsubscribers: 12.3K\ndate: 2025-01-19 16:53:21 UTC\nviews: 4.2K\n"
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text }
+            if header == "text"
+                && text.starts_with("Rust 1.84 released\n\nHighlights")
+                && text.ends_with("blog.rust-lang.org & enjoy!")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::PhotoGroup(photos) if photos.len() == 2
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::TelegramPost { chat, message_id: 421 } if chat == "@rustnews"
+    )));
+}
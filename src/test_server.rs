@@ -0,0 +1,111 @@
+//! Minimal HTTP server standing in for external services in tests
+
+use std::io::{BufRead as _, BufReader, Write as _};
+use std::net::{TcpListener, TcpStream};
+
+pub struct Route {
+    /// Path with query like `/api/thing?id=1`. Requests with other queries fall back to a route without a query.
+    path: String,
+    /// Only answer requests with an `Accept` header containing this, like content negotiation does
+    accept: Option<&'static str>,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Route {
+    pub fn new(path: &str, content_type: &'static str, body: impl Into<String>) -> Self {
+        Self {
+            path: path.to_owned(),
+            accept: None,
+            content_type,
+            body: body.into(),
+        }
+    }
+
+    pub fn json(path: &str, body: impl Into<String>) -> Self {
+        Self::new(path, "application/json", body)
+    }
+
+    pub const fn accept(mut self, accept: &'static str) -> Self {
+        self.accept = Some(accept);
+        self
+    }
+}
+
+pub struct TestServer {
+    listener: TcpListener,
+    /// Base url like `http://127.0.0.1:1234`
+    pub url: String,
+}
+
+impl TestServer {
+    pub fn new() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Should be able to bind locally");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        Self { listener, url }
+    }
+
+    /// Answer requests in the background until the test process ends. Returns the base url.
+    pub fn serve(self, routes: Vec<Route>) -> String {
+        let url = self.url.clone();
+        std::thread::spawn(move || {
+            for stream in self.listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                respond(stream, &routes);
+            }
+        });
+        url
+    }
+}
+
+fn respond(mut stream: TcpStream, routes: &[Route]) {
+    let mut reader = BufReader::new(&stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        return;
+    }
+    let mut accept = String::new();
+    loop {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => break,
+            Ok(_) if line.trim().is_empty() => break,
+            Ok(_) => {
+                if let Some((key, value)) = line.split_once(':')
+                    && key.eq_ignore_ascii_case("accept")
+                {
+                    value.trim().clone_into(&mut accept);
+                }
+            }
+        }
+    }
+
+    let target = request_line.split(' ').nth(1).unwrap_or("/");
+    let path = target.split('?').next().unwrap_or(target);
+    let accepted = |route: &&Route| route.accept.is_none_or(|wanted| accept.contains(wanted));
+    let route = routes
+        .iter()
+        .filter(accepted)
+        .find(|route| route.path == target)
+        .or_else(|| {
+            routes
+                .iter()
+                .filter(accepted)
+                .find(|route| route.path == path)
+        });
+
+    let response = route.map_or_else(
+        || "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_owned(),
+        |route| {
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                route.content_type,
+                route.body.len(),
+                route.body
+            )
+        },
+    );
+    let _: std::io::Result<()> = stream.write_all(response.as_bytes());
+}
@@ -1,37 +1,59 @@
 use std::fmt::Write as _;
 
 use anyhow::Context as _;
-use chrono::{DateTime, Utc};
-use frankenstein::TelegramApi as _;
-use frankenstein::methods::{SendAudioParams, SendMessageParams, SendPhotoParams};
 use scraper::Html;
 use serde_json::Value;
 
+use crate::analyzer::{Analyzer, Page, host_is};
+use crate::json::{deep_get, parse_timestamp};
 use crate::macros::selector;
+use crate::report::{Audio, Report, Section};
 use crate::single::Single as _;
 
+pub struct TikTok;
+
+impl Analyzer for TikTok {
+    fn name(&self) -> &'static str {
+        "tiktok"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        let uri = page.uri;
+        host_is(uri, "tiktok.com")
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        analyze(page.body)
+    }
+}
+
+/// Fields of the item which are kept verbatim for the JSON document
+const DATA_KEYS: [&str; 12] = [
+    "id",
+    "desc",
+    "createTime",
+    "stats",
+    "statsV2",
+    "author",
+    "authorStats",
+    "music",
+    "contents",
+    "contentLocation",
+    "poi",
+    "warnInfo",
+];
+
 #[expect(clippy::too_many_lines)]
-pub fn analyze(
-    bot: &frankenstein::client_ureq::Bot,
-    chat_id: i64,
-    reply_params: &frankenstein::types::ReplyParameters,
-    body: &str,
-) -> anyhow::Result<()> {
+fn analyze(body: &str) -> anyhow::Result<Report> {
     let json = extract_json(body)?;
+    let mut report = Report::default();
 
     let share_desc = json
         .get("shareMeta")
         .and_then(|value| value.get("desc"))
         .and_then(Value::as_str);
     if let Some(share_desc) = share_desc {
-        bot.send_message(
-            &SendMessageParams::builder()
-                .chat_id(chat_id)
-                .reply_parameters(reply_params.clone())
-                .text(format!("shareMeta.desc:\n\n{share_desc}"))
-                .build(),
-        )
-        .context("Should be able to send share description")?;
+        report.text(Some("shareMeta.desc"), share_desc);
     }
 
     let item = json
@@ -42,16 +64,15 @@ pub fn analyze(
     #[cfg(debug_assertions)]
     dbg!(item.as_object().unwrap().keys().collect::<Vec<_>>());
 
+    for key in DATA_KEYS {
+        if let Some(value) = item.get(key) {
+            report.insert_data(key, value.clone());
+        }
+    }
+
     let desc = item.get("desc").and_then(Value::as_str);
     if let Some(desc) = desc.filter(|desc| Some(*desc) != share_desc) {
-        bot.send_message(
-            &SendMessageParams::builder()
-                .chat_id(chat_id)
-                .reply_parameters(reply_params.clone())
-                .text(format!("desc:\n\n{desc}"))
-                .build(),
-        )
-        .context("Should be able to send description")?;
+        report.text(Some("desc"), desc);
     }
 
     let contents = item
@@ -66,14 +87,7 @@ pub fn analyze(
                 .join("\n")
         });
     if let Some(contents) = contents {
-        bot.send_message(
-            &SendMessageParams::builder()
-                .chat_id(chat_id)
-                .reply_parameters(reply_params.clone())
-                .text(format!("contents:\n\n{contents}"))
-                .build(),
-        )
-        .context("Should be able to send contents")?;
+        report.text(Some("contents"), contents);
     }
 
     let mut times = String::new();
@@ -88,45 +102,26 @@ pub fn analyze(
         writeln!(times, "takeDown: {time}").unwrap();
     }
     if !times.is_empty() {
-        bot.send_message(
-            &SendMessageParams::builder()
-                .chat_id(chat_id)
-                .reply_parameters(reply_params.clone())
-                .text(times)
-                .build(),
-        )
-        .context("Should be able to send times")?;
+        report.text(None, times);
     }
 
-    reply_json(bot, chat_id, reply_params, item, ["stats"])?;
-    reply_json(bot, chat_id, reply_params, item, ["statsV2"])?;
+    push_json(&mut report, item, ["stats"]);
+    push_json(&mut report, item, ["statsV2"]);
 
     if let Some(author) = item.get("author").and_then(Value::as_object) {
         if let Some(avatar) = author.get("avatarLarger").and_then(Value::as_str) {
-            bot.send_photo(
-                &SendPhotoParams::builder()
-                    .chat_id(chat_id)
-                    .reply_parameters(reply_params.clone())
-                    .photo(avatar.to_owned())
-                    .caption("author")
-                    .build(),
-            )
-            .expect("Should be able to send author avatar");
+            report.push(Section::Photo {
+                url: avatar.to_owned(),
+                caption: Some("author".to_owned()),
+            });
         }
 
         if let Some(signature) = author.get("signature").and_then(Value::as_str) {
-            bot.send_message(
-                &SendMessageParams::builder()
-                    .chat_id(chat_id)
-                    .reply_parameters(reply_params.clone())
-                    .text(format!("author signature:\n\n{signature}"))
-                    .build(),
-            )
-            .context("Should be able to send author signature")?;
+            report.text(Some("author signature"), signature);
         }
     }
 
-    reply_json(bot, chat_id, reply_params, item, ["authorStats"])?;
+    push_json(&mut report, item, ["authorStats"]);
 
     if let Some(music) = item.get("music").and_then(Value::as_object) {
         let mut caption = "Music behind video\n\n".to_owned();
@@ -143,15 +138,10 @@ pub fn analyze(
         }
 
         if let Some(cover) = music.get("coverLarge").and_then(Value::as_str) {
-            bot.send_photo(
-                &SendPhotoParams::builder()
-                    .chat_id(chat_id)
-                    .reply_parameters(reply_params.clone())
-                    .photo(cover.to_owned())
-                    .caption(caption.clone())
-                    .build(),
-            )
-            .expect("Should be able to send music cover");
+            report.push(Section::Photo {
+                url: cover.to_owned(),
+                caption: Some(caption.clone()),
+            });
         }
 
         if let Some(play_url) = music.get("playUrl").and_then(Value::as_str) {
@@ -159,83 +149,34 @@ pub fn analyze(
                 .get("duration")
                 .and_then(Value::as_i64)
                 .and_then(|duration| duration.try_into().ok());
-            bot.send_audio(
-                &SendAudioParams::builder()
-                    .chat_id(chat_id)
-                    .reply_parameters(reply_params.clone())
-                    .audio(play_url.to_owned())
-                    .maybe_duration(duration)
-                    .maybe_performer(artist)
-                    .maybe_title(title)
-                    .caption(caption)
-                    .build(),
-            )
-            .context("Should be able to send music")?;
+            report.push(Section::Audio(Audio {
+                url: play_url.to_owned(),
+                title: title.map(ToOwned::to_owned),
+                performer: artist.map(ToOwned::to_owned),
+                duration,
+                caption: Some(caption),
+            }));
         }
     }
 
-    reply_json(bot, chat_id, reply_params, item, ["video", "subtitleInfos"])?;
-    reply_json(bot, chat_id, reply_params, item, ["contentLocation"])?;
-    reply_json(bot, chat_id, reply_params, item, ["poi"])?;
-    reply_json(bot, chat_id, reply_params, item, ["warnInfo"])?;
+    push_json(&mut report, item, ["video", "subtitleInfos"]);
+    push_json(&mut report, item, ["contentLocation"]);
+    push_json(&mut report, item, ["poi"]);
+    push_json(&mut report, item, ["warnInfo"]);
 
-    Ok(())
+    Ok(report)
 }
 
-fn reply_json<const N: usize>(
-    bot: &frankenstein::client_ureq::Bot,
-    chat_id: i64,
-    reply_params: &frankenstein::types::ReplyParameters,
-    json: &Value,
-    keys: [&'static str; N],
-) -> anyhow::Result<()> {
+fn push_json<const N: usize>(report: &mut Report, json: &Value, keys: [&'static str; N]) {
     let header = keys.join(".");
-    if let Some(json) =
-        deep_get(json, keys).and_then(|value| serde_json::to_string_pretty(value).ok())
-    {
-        if let Err(error) = crate::telegram::send_code(
-            bot,
-            chat_id,
-            reply_params,
-            Some(&header),
-            Some("json"),
-            &json,
-        ) {
-            let text = format!("failed to send_json_message {header}: {error:#}");
-            let params = SendMessageParams::builder()
-                .chat_id(chat_id)
-                .reply_parameters(reply_params.clone())
-                .text(text)
-                .build();
-            bot.send_message(&params)
-                .context("Should be able to send send_json_message error to user")?;
-        }
+    if let Some(value) = deep_get(json, keys) {
+        report.push(Section::Json {
+            header,
+            value: value.clone(),
+        });
     } else {
-        eprintln!("tiktok send_json_message key not there: {header}");
-    }
-    Ok(())
-}
-
-fn deep_get<'json, const N: usize>(
-    mut json: &'json Value,
-    keys: [&'static str; N],
-) -> Option<&'json Value> {
-    for key in keys {
-        json = json.get(key)?;
+        eprintln!("tiktok json key not there: {header}");
     }
-    Some(json)
-}
-
-fn parse_timestamp(value: &Value) -> Option<DateTime<Utc>> {
-    let unix_timestamp = match value {
-        Value::Number(number) => number.as_i64(),
-        Value::String(str) => str.parse().ok(),
-        _ => None,
-    }?;
-    if unix_timestamp == 0 {
-        return None;
-    }
-    DateTime::from_timestamp(unix_timestamp, 0)
 }
 
 fn extract_json(html: &str) -> anyhow::Result<Value> {
@@ -259,17 +200,23 @@ fn znd_has_parseable_json() {
 }
 
 #[test]
-fn parse_timestamp_works() {
-    let expected = chrono::NaiveDate::from_ymd_opt(2025, 1, 19)
-        .unwrap()
-        .and_hms_opt(16, 53, 21)
-        .unwrap()
-        .and_utc();
-    assert_eq!(parse_timestamp(&0.into()), None);
-    assert_eq!(parse_timestamp(&Value::String("0".to_owned())), None);
-    assert_eq!(parse_timestamp(&1_737_305_601.into()), Some(expected));
-    assert_eq!(
-        parse_timestamp(&Value::String("1737305601".to_owned())),
-        Some(expected)
+fn znd_report() {
+    let html = include_str!("../test/tiktok-ZNdJnFdqC.html");
+    let report = analyze(html).unwrap();
+    let json_headers = report
+        .sections
+        .iter()
+        .filter_map(|section| match section {
+            Section::Json { header, .. } => Some(header.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(json_headers.contains(&"stats"));
+    assert!(json_headers.contains(&"statsV2"));
+    assert!(
+        report
+            .sections
+            .iter()
+            .any(|section| matches!(section, Section::Audio(_)))
     );
 }
@@ -0,0 +1,319 @@
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::analyzer::{Analyzer, Page, host_is};
+use crate::report::{Photo, Report};
+
+/// Same endpoint the embedded tweets use. Can be replaced with `TWITTER_API`.
+const DEFAULT_API: &str = "https://cdn.syndication.twimg.com";
+
+pub struct Twitter;
+
+impl Analyzer for Twitter {
+    fn name(&self) -> &'static str {
+        "twitter"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        let uri = page.uri;
+        (host_is(uri, "x.com") || host_is(uri, "twitter.com")) && status_id(uri.path()).is_some()
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        let id = status_id(page.uri.path()).expect("matches ensures a status id");
+        let api = std::env::var("TWITTER_API").unwrap_or_else(|_| DEFAULT_API.to_owned());
+        let tweet = fetch(&api, id)?;
+        Ok(analyze(&tweet))
+    }
+}
+
+/// `/<user>/status/<id>` or `/i/web/status/<id>`
+fn status_id(path: &str) -> Option<&str> {
+    let mut segments = path.split('/');
+    segments.find(|segment| *segment == "status")?;
+    segments
+        .next()
+        .filter(|id| !id.is_empty() && id.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+fn fetch(api: &str, id: &str) -> anyhow::Result<Value> {
+    let token = token(id)?;
+    crate::http::get_json(&format!("{api}/tweet-result?id={id}&token={token}"), &[])
+}
+
+/// The embed widget derives the token from the id:
+/// `((Number(id) / 1e15) * Math.PI).toString(36).replace(/(0+|\.)/g, '')`
+fn token(id: &str) -> anyhow::Result<String> {
+    let id = id.parse::<f64>()?;
+    let token = to_radix_36(id / 1e15 * std::f64::consts::PI);
+    Ok(token.replace(['0', '.'], ""))
+}
+
+/// `Number.prototype.toString(36)` of JavaScript for small positive numbers.
+///
+/// Fractional digits are only written up to the precision of the double like V8 does.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::float_cmp
+)]
+fn to_radix_36(value: f64) -> String {
+    const RADIX: u32 = 36;
+    let digit_char =
+        |digit: u32| char::from_digit(digit, RADIX).expect("digit should be below radix");
+
+    let mut integer = value.floor() as u64;
+    let mut fraction = value - value.floor();
+    let mut delta = (0.5 * (f64::from_bits(value.to_bits() + 1) - value)).max(f64::from_bits(1));
+    let mut fraction_digits = Vec::new();
+    if fraction >= delta {
+        loop {
+            fraction *= f64::from(RADIX);
+            delta *= f64::from(RADIX);
+            let digit = fraction as u32;
+            fraction_digits.push(digit);
+            fraction -= f64::from(digit);
+            // Round to even
+            if (fraction > 0.5 || (fraction == 0.5 && digit % 2 == 1)) && fraction + delta > 1.0 {
+                // Carry over into the already written digits
+                loop {
+                    match fraction_digits.pop() {
+                        None => {
+                            integer += 1;
+                            break;
+                        }
+                        Some(digit) if digit + 1 < RADIX => {
+                            fraction_digits.push(digit + 1);
+                            break;
+                        }
+                        Some(_) => {}
+                    }
+                }
+                break;
+            }
+            if fraction < delta {
+                break;
+            }
+        }
+    }
+
+    let mut integer_digits = Vec::new();
+    loop {
+        integer_digits.push(digit_char((integer % u64::from(RADIX)) as u32));
+        integer /= u64::from(RADIX);
+        if integer == 0 {
+            break;
+        }
+    }
+    let mut result = integer_digits.into_iter().rev().collect::<String>();
+    if !fraction_digits.is_empty() {
+        result.push('.');
+        result.extend(fraction_digits.into_iter().map(digit_char));
+    }
+    result
+}
+
+fn analyze(tweet: &Value) -> Report {
+    let mut report = Report::default();
+
+    report.text(Some(&author(tweet)), text(tweet));
+
+    let mut info = String::new();
+    if let Some(time) = tweet.get("created_at").and_then(parse_time) {
+        writeln!(info, "created_at: {time}").unwrap();
+    }
+    for key in [
+        "favorite_count",
+        "retweet_count",
+        "reply_count",
+        "conversation_count",
+    ] {
+        if let Some(count) = tweet.get(key).and_then(Value::as_u64) {
+            writeln!(info, "{key}: {count}").unwrap();
+        }
+    }
+    if let Some(reply_to) = tweet.get("in_reply_to_screen_name").and_then(Value::as_str) {
+        let status = tweet
+            .get("in_reply_to_status_id_str")
+            .and_then(Value::as_str);
+        if let Some(status) = status {
+            writeln!(
+                info,
+                "in reply to: https://x.com/{reply_to}/status/{status}"
+            )
+            .unwrap();
+        } else {
+            writeln!(info, "in reply to: @{reply_to}").unwrap();
+        }
+    }
+    report.text(None, info);
+
+    if let Some(quoted) = tweet.get("quoted_tweet") {
+        report.text(Some(&format!("quoting {}", author(quoted))), text(quoted));
+    }
+
+    let media = tweet
+        .get("mediaDetails")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let photos = media
+        .iter()
+        .filter(|media| media.get("type").and_then(Value::as_str) == Some("photo"))
+        .filter_map(|media| {
+            Some(Photo {
+                url: media.get("media_url_https")?.as_str()?.to_owned(),
+                caption: media
+                    .get("ext_alt_text")
+                    .and_then(Value::as_str)
+                    .map(ToOwned::to_owned),
+            })
+        })
+        .collect::<Vec<_>>();
+    let videos = media
+        .iter()
+        .filter(|media| media.get("type").and_then(Value::as_str) != Some("photo"))
+        .count();
+
+    report.insert_data("id", tweet.get("id_str").cloned());
+    report.insert_data("text", tweet.get("text").cloned());
+    report.insert_data("user", tweet.get("user").cloned());
+    report.insert_data("created_at", tweet.get("created_at").cloned());
+    report.insert_data("favorite_count", tweet.get("favorite_count").cloned());
+    report.insert_data(
+        "conversation_count",
+        tweet.get("conversation_count").cloned(),
+    );
+    report.insert_data(
+        "in_reply_to_status_id",
+        tweet.get("in_reply_to_status_id_str").cloned(),
+    );
+    report.insert_data(
+        "quoted_tweet_id",
+        tweet
+            .get("quoted_tweet")
+            .and_then(|quoted| quoted.get("id_str"))
+            .cloned(),
+    );
+    report.insert_data(
+        "photos",
+        photos
+            .iter()
+            .map(|photo| photo.url.clone())
+            .collect::<Vec<_>>(),
+    );
+    report.insert_data("videos", videos);
+
+    report.photos(photos);
+    if videos > 0 {
+        // yt-dlp already downloads the videos of the status
+        report.text(
+            None,
+            format!("{videos} video(s), use /video to download them"),
+        );
+    }
+
+    report
+}
+
+fn author(tweet: &Value) -> String {
+    let user = tweet.get("user");
+    let screen_name = user
+        .and_then(|user| user.get("screen_name"))
+        .and_then(Value::as_str)
+        .unwrap_or("unknown");
+    let name = user
+        .and_then(|user| user.get("name"))
+        .and_then(Value::as_str);
+    name.map_or_else(
+        || format!("@{screen_name}"),
+        |name| format!("@{screen_name} ({name})"),
+    )
+}
+
+fn text(tweet: &Value) -> String {
+    tweet
+        .get("text")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_owned()
+}
+
+fn parse_time(value: &Value) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.as_str()?)
+        .ok()
+        .map(|time| time.to_utc())
+}
+
+#[test]
+fn status_id_works() {
+    assert_eq!(
+        status_id("/harzhiker/status/1881023456789012345"),
+        Some("1881023456789012345")
+    );
+    assert_eq!(status_id("/i/web/status/123/photo/1"), Some("123"));
+    assert_eq!(status_id("/harzhiker"), None);
+    assert_eq!(status_id("/harzhiker/status/"), None);
+}
+
+#[test]
+fn fixture_report() {
+    use crate::report::Section;
+
+    let json = include_str!("../test/twitter-1881023456789012345.json");
+    let report = analyze(&serde_json::from_str(json).unwrap());
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text }
+            if header == "@harzhiker (Harz Hiker)" && text.starts_with("@rustybots Great video!")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: None, text }
+            if text.contains("created_at: 2025-01-19 16:53:21 UTC")
+                && text.contains("favorite_count: 321")
+                && text.contains("in reply to: https://x.com/rustybots/status/1881000000000000000")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), .. } if header == "quoting @rustybots (Rusty Bots)"
+    )));
+    let photos = report
+        .sections
+        .iter()
+        .find_map(|section| match section {
+            Section::PhotoGroup(photos) => Some(photos),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(photos.len(), 2);
+    assert_eq!(photos[0].caption.as_deref(), Some("View from the summit"));
+    assert_eq!(report.data["videos"], 1);
+}
+
+#[test]
+fn token_matches_embed_widget() {
+    // Generated with the JavaScript of the embed widget
+    assert_eq!(token("1881023456789012345").unwrap(), "4k5eqodj87m");
+    assert_eq!(token("1234567890123456789").unwrap(), "2zqic77uqyk");
+    assert_eq!(token("1600000000000000000").unwrap(), "3vmjqiybmlu");
+    assert_eq!(token("463440424141459456").unwrap(), "14fxvks611f");
+    assert_eq!(token("20").unwrap(), "6dq1a2xwd93");
+}
+
+#[test]
+fn tweet_via_stand_in_api() {
+    use crate::test_server::{Route, TestServer};
+
+    let id = "1881023456789012345";
+    let api = TestServer::new().serve(vec![Route::json(
+        &format!("/tweet-result?id={id}&token=4k5eqodj87m"),
+        include_str!("../test/twitter-1881023456789012345.json"),
+    )]);
+    let tweet = fetch(&api, id).unwrap();
+    let report = analyze(&tweet);
+    assert_eq!(report.data["id"], id);
+    assert_eq!(report.data["videos"], 1);
+}
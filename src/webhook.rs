@@ -0,0 +1,163 @@
+//! Receive updates from Telegram via HTTP instead of long polling
+
+use std::io::Read as _;
+
+use frankenstein::updates::Update;
+use tiny_http::{Method, Request, Response, Server};
+
+/// Telegram sends the `secret_token` of `setWebhook` with every request
+const SECRET_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
+/// Updates are way smaller, anything bigger is not from Telegram
+const MAX_BODY: u64 = 1024 * 1024;
+
+pub struct Webhook {
+    server: Server,
+    path: String,
+    pub secret: String,
+    /// Public url which is registered with Telegram. Without it the webhook has to be set up another way.
+    pub url: Option<String>,
+}
+
+impl Webhook {
+    /// Webhook mode is used when `WEBHOOK_BIND` is set
+    pub fn from_env() -> Option<Self> {
+        let bind = std::env::var("WEBHOOK_BIND").ok()?;
+        let path = std::env::var("WEBHOOK_PATH").unwrap_or_else(|_| "/".to_owned());
+        let secret = std::env::var("WEBHOOK_SECRET")
+            .expect("Should have WEBHOOK_SECRET specified in environment");
+        let url = std::env::var("WEBHOOK_URL").ok();
+        let mut webhook = Self::new(&bind, path, secret)
+            .expect("Should be able to listen on WEBHOOK_BIND for the webhook");
+        webhook.url = url;
+        Some(webhook)
+    }
+
+    pub fn new(bind: &str, path: String, secret: String) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            !secret.is_empty()
+                && secret.len() <= 256
+                && secret
+                    .bytes()
+                    .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-'),
+            "Webhook secret should be 1-256 characters of A-Z, a-z, 0-9, _ and -"
+        );
+        let server = Server::http(bind).map_err(|error| anyhow::anyhow!(error))?;
+        println!("Webhook listens on {}{path}", server.server_addr());
+        Ok(Self {
+            server,
+            path,
+            secret,
+            url: None,
+        })
+    }
+
+    #[cfg(test)]
+    fn local_url(&self) -> String {
+        let addr = self.server.server_addr().to_ip().unwrap();
+        format!("http://{addr}{}", self.path)
+    }
+
+    /// Block until the next valid update arrives. Everything else is answered with an error status.
+    pub fn next_update(&self) -> Update {
+        loop {
+            let request = match self.server.recv() {
+                Ok(request) => request,
+                Err(error) => {
+                    eprintln!("Webhook failed to receive request: {error:#}");
+                    continue;
+                }
+            };
+            if let Some(update) = self.check(request) {
+                return update;
+            }
+        }
+    }
+
+    /// Answer the request and return the update when it is valid.
+    /// Updates are acknowledged before they are handled as Telegram retries slow responses.
+    fn check(&self, mut request: Request) -> Option<Update> {
+        let result = self.parse(&mut request);
+        let status = result.as_ref().map_or_else(|status| *status, |_| 200);
+        if let Err(error) = request.respond(Response::empty(status)) {
+            eprintln!("Webhook failed to respond: {error:#}");
+        }
+        if status != 200 {
+            eprintln!("Webhook rejected request with {status}");
+        }
+        result.ok()
+    }
+
+    /// Status code of the rejection when the request is not a valid update
+    fn parse(&self, request: &mut Request) -> Result<Update, u16> {
+        if request.url() != self.path {
+            return Err(404);
+        }
+        if *request.method() != Method::Post {
+            return Err(405);
+        }
+        let secret = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(SECRET_HEADER))
+            .map(|header| header.value.as_str());
+        if secret != Some(self.secret.as_str()) {
+            return Err(401);
+        }
+        let mut body = String::new();
+        request
+            .as_reader()
+            .take(MAX_BODY)
+            .read_to_string(&mut body)
+            .map_err(|error| {
+                eprintln!("Webhook failed to read body: {error:#}");
+                400_u16
+            })?;
+        serde_json::from_str(&body).map_err(|error| {
+            eprintln!("Webhook got an invalid update: {error:#}");
+            400
+        })
+    }
+}
+
+#[test]
+fn receives_recorded_updates() {
+    let webhook = Webhook::new("127.0.0.1:0", "/telegram".to_owned(), "s3cr3t".to_owned()).unwrap();
+    let url = webhook.local_url();
+    let (sender, updates) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        loop {
+            sender.send(webhook.next_update()).unwrap();
+        }
+    });
+
+    let post = |url: &str, secret: &str, body: &str| {
+        ureq::post(url)
+            .header(SECRET_HEADER, secret)
+            .config()
+            .http_status_as_error(false)
+            .build()
+            .send(body)
+            .unwrap()
+            .status()
+            .as_u16()
+    };
+    let update = include_str!("../test/telegram-update-message.json");
+
+    assert_eq!(post(&url, "wrong", update), 401);
+    assert_eq!(post(&format!("{url}/other"), "s3cr3t", update), 404);
+    assert_eq!(post(&url, "s3cr3t", "{}"), 400);
+    assert_eq!(post(&url, "s3cr3t", update), 200);
+
+    let received = updates
+        .recv_timeout(std::time::Duration::from_secs(5))
+        .unwrap();
+    assert_eq!(received.update_id, 123_456_789);
+    assert!(updates.try_recv().is_err());
+}
+
+#[test]
+fn secret_is_validated() {
+    assert!(Webhook::new("127.0.0.1:0", "/".to_owned(), String::new()).is_err());
+    assert!(Webhook::new("127.0.0.1:0", "/".to_owned(), "no spaces".to_owned()).is_err());
+}
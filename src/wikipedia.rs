@@ -0,0 +1,192 @@
+use std::fmt::Write as _;
+
+use serde_json::Value;
+
+use crate::analyzer::{Analyzer, Page, host_is};
+use crate::json::deep_get;
+use crate::report::{Report, Section};
+use crate::split::CAPTION_LIMIT;
+
+pub struct Wikipedia;
+
+impl Analyzer for Wikipedia {
+    fn name(&self) -> &'static str {
+        "wikipedia"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        let uri = page.uri;
+        host_is(uri, "wikipedia.org") && article_title(uri.path()).is_some()
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        let title = article_title(page.uri.path()).expect("matches ensures an article title");
+        let host = page.uri.host().expect("matches ensures a host");
+        let base = format!("https://{host}");
+        let summary =
+            crate::http::get_json(&format!("{base}/api/rest_v1/page/summary/{title}"), &[])?;
+        // The language editions are a nice to have and not worth failing the summary for
+        let langlinks = crate::http::get_json(
+            &format!(
+                "{base}/w/api.php?action=query&prop=langlinks&llprop=autonym&lllimit=max&format=json&formatversion=2&titles={title}"
+            ),
+            &[],
+        )
+        .ok();
+        Ok(analyze(&summary, langlinks.as_ref()))
+    }
+}
+
+/// `/wiki/<title>` as it is in the path, still percent encoded
+fn article_title(path: &str) -> Option<&str> {
+    path.strip_prefix("/wiki/")
+        .filter(|title| !title.is_empty() && !title.contains('/'))
+}
+
+fn analyze(summary: &Value, langlinks: Option<&Value>) -> Report {
+    let mut report = Report::default();
+    let field = |key| summary.get(key).and_then(Value::as_str);
+
+    let title = field("title").unwrap_or_default();
+    let mut caption = title.to_owned();
+    if let Some(description) = field("description") {
+        write!(caption, "\n{description}").unwrap();
+    }
+    let extract = field("extract").filter(|extract| !extract.is_empty());
+    let with_extract = extract.map(|extract| format!("{caption}\n\n{extract}"));
+    let (caption, extract) = match with_extract {
+        Some(with_extract) if with_extract.encode_utf16().count() <= CAPTION_LIMIT => {
+            (with_extract, None)
+        }
+        _ => (caption, extract),
+    };
+
+    let thumbnail = deep_get(summary, ["thumbnail", "source"]).and_then(Value::as_str);
+    if let Some(url) = thumbnail {
+        report.push(Section::Photo {
+            url: url.to_owned(),
+            caption: Some(caption),
+        });
+    } else {
+        report.text(None, caption);
+    }
+    if let Some(extract) = extract {
+        report.text(Some("extract"), extract);
+    }
+
+    let mut info = String::new();
+    if let Some(kind) = field("type").filter(|kind| *kind != "standard") {
+        writeln!(info, "type: {kind}").unwrap();
+    }
+    let lat = deep_get(summary, ["coordinates", "lat"]).and_then(Value::as_f64);
+    let lon = deep_get(summary, ["coordinates", "lon"]).and_then(Value::as_f64);
+    if let (Some(lat), Some(lon)) = (lat, lon) {
+        writeln!(info, "coordinates: {lat}, {lon}").unwrap();
+    }
+    if let Some(timestamp) = field("timestamp") {
+        writeln!(info, "last modified: {timestamp}").unwrap();
+    }
+    if let Some(item) = field("wikibase_item") {
+        writeln!(info, "wikidata: https://www.wikidata.org/wiki/{item}").unwrap();
+    }
+    if let Some(image) = deep_get(summary, ["originalimage", "source"]).and_then(Value::as_str) {
+        writeln!(info, "original image: {image}").unwrap();
+    }
+    if !info.is_empty() {
+        report.text(None, info);
+    }
+
+    let languages = langlinks
+        .and_then(|langlinks| deep_get(langlinks, ["query", "pages"]))
+        .and_then(Value::as_array)
+        .and_then(|pages| pages.first())
+        .and_then(|page| page.get("langlinks"))
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut editions = String::new();
+    for language in languages {
+        let lang = language.get("lang").and_then(Value::as_str);
+        let title = language.get("title").and_then(Value::as_str);
+        let (Some(lang), Some(title)) = (lang, title) else {
+            continue;
+        };
+        let autonym = language
+            .get("autonym")
+            .and_then(Value::as_str)
+            .unwrap_or(lang);
+        let path = title.replace(' ', "_");
+        writeln!(
+            editions,
+            "{lang} ({autonym}): https://{lang}.wikipedia.org/wiki/{path}"
+        )
+        .unwrap();
+    }
+    if !editions.is_empty() {
+        report.text(
+            Some(&format!("{} language editions", languages.len())),
+            editions,
+        );
+    }
+
+    report.insert_data("title", title);
+    report.insert_data("description", summary.get("description").cloned());
+    report.insert_data("extract", summary.get("extract").cloned());
+    report.insert_data("thumbnail", thumbnail);
+    report.insert_data("coordinates", summary.get("coordinates").cloned());
+    report.insert_data("timestamp", summary.get("timestamp").cloned());
+    report.insert_data("wikibase_item", summary.get("wikibase_item").cloned());
+    report.insert_data(
+        "languages",
+        languages
+            .iter()
+            .filter_map(|language| language.get("lang").cloned())
+            .collect::<Vec<_>>(),
+    );
+
+    report
+}
+
+#[test]
+fn article_title_works() {
+    assert_eq!(article_title("/wiki/Brocken"), Some("Brocken"));
+    assert_eq!(
+        article_title("/wiki/Caf%C3%A9_racer"),
+        Some("Caf%C3%A9_racer")
+    );
+    assert_eq!(article_title("/wiki/"), None);
+    assert_eq!(article_title("/w/index.php"), None);
+}
+
+#[test]
+fn brocken_report() {
+    let summary = include_str!("../test/wikipedia-Brocken-summary.json");
+    let langlinks = include_str!("../test/wikipedia-Brocken-langlinks.json");
+    let report = analyze(
+        &serde_json::from_str(summary).unwrap(),
+        Some(&serde_json::from_str(langlinks).unwrap()),
+    );
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Photo { url, caption: Some(caption) }
+            if url.ends_with("320px-Brocken_summit.jpg")
+                && caption.starts_with("Brocken\nHighest peak of the Harz")
+                && caption.contains("\n\nThe Brocken, sometimes referred to as the Blocksberg")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: None, text }
+            if text.contains("coordinates: 51.79916667, 10.61555556")
+                && text.contains("last modified: 2025-01-19T16:53:21Z")
+    )));
+    assert!(report.sections.iter().any(|section| matches!(
+        section,
+        Section::Text { header: Some(header), text }
+            if header == "4 language editions"
+                && text.contains("nl (Nederlands): https://nl.wikipedia.org/wiki/Brocken_(berg)")
+    )));
+    assert_eq!(
+        report.data["languages"],
+        serde_json::json!(["de", "fr", "nl", "ru"])
+    );
+}
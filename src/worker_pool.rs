@@ -0,0 +1,206 @@
+//! Run jobs concurrently while keeping the order of jobs with the same key, like the same chat
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Condvar, LazyLock, Mutex, MutexGuard};
+use std::thread::Scope;
+
+const DEFAULT_WORKERS: usize = 4;
+
+/// Amount of jobs running at the same time.
+///
+/// Can be configured with `WORKERS`.
+pub static WORKERS: LazyLock<usize> = LazyLock::new(|| {
+    std::env::var("WORKERS")
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .filter(|workers| *workers > 0)
+        .unwrap_or(DEFAULT_WORKERS)
+});
+
+struct Queue<Key, Job> {
+    /// Jobs not yet started per key in their order
+    waiting: HashMap<Key, VecDeque<Job>>,
+    /// Keys with waiting jobs which are not busy in the order they are picked up
+    ready: VecDeque<Key>,
+    /// Keys with a running job
+    busy: HashSet<Key>,
+    /// Workers waiting for something to do
+    idle: usize,
+    closed: bool,
+}
+
+struct Shared<Key, Job> {
+    queue: Mutex<Queue<Key, Job>>,
+    changed: Condvar,
+}
+
+impl<Key, Job> Shared<Key, Job> {
+    fn lock(&self) -> MutexGuard<'_, Queue<Key, Job>> {
+        self.queue
+            .lock()
+            .expect("Should be able to lock worker queue")
+    }
+}
+
+/// Workers stop once the pool is dropped and all queued jobs are done
+pub struct WorkerPool<Key, Job> {
+    shared: Arc<Shared<Key, Job>>,
+}
+
+impl<Key: Copy + Eq + Hash + Debug + Send, Job: Send> WorkerPool<Key, Job> {
+    /// Spawn the workers in the scope. Jobs of the same key never run concurrently.
+    pub fn new<'scope>(
+        scope: &'scope Scope<'scope, '_>,
+        workers: usize,
+        handle: impl Fn(Job) + Send + Sync + 'scope,
+    ) -> Self
+    where
+        Key: 'scope,
+        Job: 'scope,
+    {
+        let shared = Arc::new(Shared {
+            queue: Mutex::new(Queue {
+                waiting: HashMap::new(),
+                ready: VecDeque::new(),
+                busy: HashSet::new(),
+                idle: 0,
+                closed: false,
+            }),
+            changed: Condvar::new(),
+        });
+        let handle = Arc::new(handle);
+        for _ in 0..workers {
+            let shared = Arc::clone(&shared);
+            let handle = Arc::clone(&handle);
+            scope.spawn(move || work(&shared, &*handle));
+        }
+        Self { shared }
+    }
+
+    /// Queue the job. Returns its position when it has to wait for other jobs.
+    pub fn push(&self, key: Key, job: Job) -> Option<usize> {
+        let mut queue = self.shared.lock();
+        let waiting = queue.waiting.values().map(VecDeque::len).sum::<usize>();
+        let busy = queue.busy.contains(&key);
+        let starts = !busy && !queue.waiting.contains_key(&key) && queue.idle > queue.ready.len();
+
+        let jobs = queue.waiting.entry(key).or_default();
+        jobs.push_back(job);
+        if jobs.len() == 1 && !busy {
+            queue.ready.push_back(key);
+        }
+        drop(queue);
+        self.shared.changed.notify_one();
+
+        (!starts).then_some(waiting.saturating_add(1))
+    }
+}
+
+impl<Key, Job> Drop for WorkerPool<Key, Job> {
+    fn drop(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.changed.notify_all();
+    }
+}
+
+fn work<Key: Copy + Eq + Hash + Debug, Job>(shared: &Shared<Key, Job>, handle: &dyn Fn(Job)) {
+    loop {
+        let mut queue = shared.lock();
+        let (key, job) = loop {
+            if let Some(key) = queue.ready.pop_front() {
+                let jobs = queue
+                    .waiting
+                    .get_mut(&key)
+                    .expect("Ready keys should have waiting jobs");
+                let job = jobs.pop_front().expect("Waiting jobs should not be empty");
+                if jobs.is_empty() {
+                    queue.waiting.remove(&key);
+                }
+                queue.busy.insert(key);
+                break (key, job);
+            }
+            if queue.closed {
+                return;
+            }
+            queue.idle += 1;
+            queue = shared
+                .changed
+                .wait(queue)
+                .expect("Should be able to wait for worker queue");
+            queue.idle -= 1;
+        };
+        drop(queue);
+
+        // A panicking job should not take the worker and the key with it
+        if std::panic::catch_unwind(AssertUnwindSafe(|| handle(job))).is_err() {
+            eprintln!("Worker job of {key:?} panicked");
+        }
+
+        let mut queue = shared.lock();
+        queue.busy.remove(&key);
+        if queue.waiting.contains_key(&key) {
+            queue.ready.push_back(key);
+            drop(queue);
+            shared.changed.notify_one();
+        }
+    }
+}
+
+#[test]
+fn keeps_order_per_key() {
+    use std::time::Duration;
+
+    let done = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        let pool = WorkerPool::new(scope, 3, |(key, index): (u8, u64)| {
+            // Later jobs are faster to provoke reordering
+            std::thread::sleep(Duration::from_millis(20u64.saturating_sub(index * 5)));
+            done.lock().unwrap().push((key, index));
+        });
+        for index in 0..4 {
+            for key in [1, 2] {
+                pool.push(key, (key, index));
+            }
+        }
+    });
+
+    let done = done.into_inner().unwrap();
+    assert_eq!(done.len(), 8);
+    for key in [1, 2] {
+        let order = done
+            .iter()
+            .filter(|(done_key, _)| *done_key == key)
+            .map(|(_, index)| *index)
+            .collect::<Vec<_>>();
+        assert_eq!(order, [0, 1, 2, 3]);
+    }
+}
+
+#[test]
+fn reports_position_when_busy() {
+    use std::sync::mpsc;
+
+    let (release, blocked) = mpsc::channel::<()>();
+    let blocked = Mutex::new(blocked);
+    std::thread::scope(|scope| {
+        let pool = WorkerPool::new(scope, 1, |()| {
+            blocked.lock().unwrap().recv().unwrap();
+        });
+        // Wait for the worker to be idle
+        while pool.shared.lock().idle == 0 {
+            std::thread::yield_now();
+        }
+        assert_eq!(pool.push(1, ()), None);
+        while !pool.shared.lock().busy.contains(&1) {
+            std::thread::yield_now();
+        }
+        assert_eq!(pool.push(2, ()), Some(1));
+        assert_eq!(pool.push(1, ()), Some(2));
+        for _ in 0..3 {
+            release.send(()).unwrap();
+        }
+    });
+}
@@ -0,0 +1,275 @@
+use std::fmt::Write as _;
+
+use anyhow::Context as _;
+use chrono::DateTime;
+use scraper::Html;
+use serde_json::Value;
+
+use crate::analyzer::{Analyzer, Page, host_is};
+use crate::json::{deep_get, find_key, parse_number};
+use crate::macros::selector;
+use crate::report::Report;
+
+/// Metadata from `ytInitialPlayerResponse` and `ytInitialData` without downloading anything
+pub struct YouTube;
+
+impl Analyzer for YouTube {
+    fn name(&self) -> &'static str {
+        "youtube"
+    }
+
+    fn matches(&self, page: &Page) -> bool {
+        let uri = page.uri;
+        host_is(uri, "youtube.com")
+            && ["/watch", "/shorts/", "/live/"]
+                .iter()
+                .any(|prefix| uri.path().starts_with(prefix))
+    }
+
+    fn analyze(&self, page: &Page) -> anyhow::Result<Report> {
+        analyze(page.body)
+    }
+}
+
+fn analyze(body: &str) -> anyhow::Result<Report> {
+    let player = extract_json(body, "ytInitialPlayerResponse")
+        .context("Should have ytInitialPlayerResponse")?;
+    let initial_data = extract_json(body, "ytInitialData");
+    let mut report = Report::default();
+
+    let details = player.get("videoDetails");
+    let microformat = deep_get(&player, ["microformat", "playerMicroformatRenderer"]);
+    let detail = |key| details.and_then(|details| details.get(key));
+    let micro = |key| microformat.and_then(|microformat| microformat.get(key));
+
+    let mut info = String::new();
+    if let Some(title) = detail("title").and_then(Value::as_str) {
+        writeln!(info, "{title}\n").unwrap();
+    }
+    if let Some(channel) = detail("author").and_then(Value::as_str) {
+        let profile = micro("ownerProfileUrl").and_then(Value::as_str);
+        if let Some(profile) = profile {
+            writeln!(info, "channel: {channel} ({profile})").unwrap();
+        } else {
+            writeln!(info, "channel: {channel}").unwrap();
+        }
+    }
+    for key in ["uploadDate", "publishDate"] {
+        if let Some(date) = micro(key).and_then(parse_date) {
+            writeln!(info, "{key}: {date}").unwrap();
+        }
+    }
+    if let Some(seconds) = detail("lengthSeconds").and_then(parse_number) {
+        writeln!(info, "duration: {}", format_duration(seconds)).unwrap();
+    }
+    if let Some(views) = detail("viewCount").and_then(parse_number) {
+        writeln!(info, "viewCount: {views}").unwrap();
+    }
+    if let Some(category) = micro("category").and_then(Value::as_str) {
+        writeln!(info, "category: {category}").unwrap();
+    }
+    if detail("isLiveContent").and_then(Value::as_bool) == Some(true) {
+        writeln!(info, "live content").unwrap();
+    }
+    if !info.is_empty() {
+        report.text(None, info);
+    }
+
+    let restrictions = restrictions(&player);
+    if !restrictions.is_empty() {
+        report.text(Some("restrictions"), restrictions.join("\n"));
+    }
+
+    let chapters = initial_data.as_ref().map(chapters).unwrap_or_default();
+    if !chapters.is_empty() {
+        let mut text = String::new();
+        for (start, title) in &chapters {
+            writeln!(text, "{} {title}", format_duration(start / 1000)).unwrap();
+        }
+        report.text(Some("chapters"), text);
+    }
+
+    let captions = caption_tracks(&player);
+    if !captions.is_empty() {
+        report.text(Some("captions"), captions.join("\n"));
+    }
+
+    if let Some(description) = detail("shortDescription")
+        .and_then(Value::as_str)
+        .filter(|description| !description.is_empty())
+    {
+        report.text(Some("description"), description);
+    }
+
+    report.insert_data("videoDetails", details.cloned());
+    report.insert_data("microformat", microformat.cloned());
+    report.insert_data(
+        "playabilityStatus",
+        player.get("playabilityStatus").cloned(),
+    );
+    report.insert_data("restrictions", restrictions);
+    report.insert_data(
+        "chapters",
+        chapters
+            .iter()
+            .map(|(start, title)| serde_json::json!({"startMillis": start, "title": title}))
+            .collect::<Vec<_>>(),
+    );
+    report.insert_data("captions", captions);
+
+    Ok(report)
+}
+
+/// Age, region and other playability restrictions
+fn restrictions(player: &Value) -> Vec<String> {
+    let mut restrictions = Vec::new();
+    let status = deep_get(player, ["playabilityStatus", "status"]).and_then(Value::as_str);
+    if let Some(status) = status.filter(|status| *status != "OK") {
+        let reason = deep_get(player, ["playabilityStatus", "reason"])
+            .and_then(Value::as_str)
+            .unwrap_or("no reason given");
+        restrictions.push(format!("playability {status}: {reason}"));
+    }
+    if deep_get(player, ["playabilityStatus", "desktopLegacyAgeGateReason"]).is_some()
+        || deep_get(
+            player,
+            ["microformat", "playerMicroformatRenderer", "isFamilySafe"],
+        )
+        .and_then(Value::as_bool)
+            == Some(false)
+    {
+        restrictions.push("age restricted".to_owned());
+    }
+    if let Some(countries) = deep_get(
+        player,
+        [
+            "microformat",
+            "playerMicroformatRenderer",
+            "availableCountries",
+        ],
+    )
+    .and_then(Value::as_array)
+    {
+        let countries = countries
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>();
+        // Unrestricted videos list pretty much every country
+        if countries.len() < 200 {
+            restrictions.push(format!("only available in: {}", countries.join(" ")));
+        }
+    }
+    restrictions
+}
+
+/// Start in milliseconds and title of each chapter
+fn chapters(initial_data: &Value) -> Vec<(u64, String)> {
+    find_key(initial_data, "chapters")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|chapter| {
+            let chapter = chapter.get("chapterRenderer")?;
+            let start = chapter.get("timeRangeStartMillis").and_then(parse_number)?;
+            let title = deep_get(chapter, ["title", "simpleText"])?.as_str()?;
+            Some((start, title.to_owned()))
+        })
+        .collect()
+}
+
+fn caption_tracks(player: &Value) -> Vec<String> {
+    deep_get(
+        player,
+        [
+            "captions",
+            "playerCaptionsTracklistRenderer",
+            "captionTracks",
+        ],
+    )
+    .and_then(Value::as_array)
+    .into_iter()
+    .flatten()
+    .filter_map(|track| {
+        let language = track.get("languageCode")?.as_str()?;
+        let name = deep_get(track, ["name", "simpleText"])
+            .and_then(Value::as_str)
+            .unwrap_or(language);
+        let kind = track.get("kind").and_then(Value::as_str);
+        Some(kind.map_or_else(
+            || format!("{language}: {name}"),
+            |kind| format!("{language}: {name} ({kind})"),
+        ))
+    })
+    .collect()
+}
+
+fn parse_date(value: &Value) -> Option<DateTime<chrono::FixedOffset>> {
+    DateTime::parse_from_rfc3339(value.as_str()?).ok()
+}
+
+fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds / 60) % 60;
+    let seconds = seconds % 60;
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Parse the object assigned like `var ytInitialData = {…};` in a script
+fn extract_json(html: &str, variable: &str) -> Option<Value> {
+    let needle = format!("{variable} = ");
+    Html::parse_document(html)
+        .select(selector!("script"))
+        .find_map(|element| {
+            let script = element.inner_html();
+            let start = script.find(&needle)?.saturating_add(needle.len());
+            // Only parse the object, ignore the rest of the script
+            serde_json::Deserializer::from_str(&script[start..])
+                .into_iter::<Value>()
+                .next()?
+                .ok()
+        })
+}
+
+#[test]
+fn q8m_report() {
+    let html = include_str!("../test/youtube-Q8mPxa0Ez1s.html");
+    let report = analyze(html).unwrap();
+    let text = |wanted: Option<&str>| {
+        report
+            .sections
+            .iter()
+            .find_map(|section| match section {
+                crate::report::Section::Text { header, text } if header.as_deref() == wanted => {
+                    Some(text.as_str())
+                }
+                _ => None,
+            })
+            .unwrap()
+    };
+    let info = text(None);
+    assert!(info.starts_with("Building a tiny Telegram bot in Rust\n"));
+    assert!(info.contains("channel: Rusty Bots (http://www.youtube.com/@rustybots)"));
+    assert!(info.contains("uploadDate: 2025-01-19 08:53:21 -08:00"));
+    assert!(info.contains("duration: 12:34"));
+    assert!(info.contains("viewCount: 48213"));
+    assert_eq!(
+        text(Some("chapters")),
+        "0:00 Intro\n2:10 Setup\n8:45 Deploy\n"
+    );
+    assert_eq!(
+        text(Some("captions")),
+        "en: English\nde: German (auto-generated) (asr)"
+    );
+    assert_eq!(text(Some("restrictions")), "only available in: AT CH DE");
+}
+
+#[test]
+fn format_duration_works() {
+    assert_eq!(format_duration(0), "0:00");
+    assert_eq!(format_duration(754), "12:34");
+    assert_eq!(format_duration(3_723), "1:02:03");
+}
@@ -1,139 +1,169 @@
-use frankenstein::TelegramApi as _;
-use frankenstein::methods::{
-    DeleteMessageParams, EditMessageTextParams, SendChatActionParams, SendMessageParams,
-    SendVideoParams,
-};
-use frankenstein::types::ChatAction;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use anyhow::Context as _;
 
 use crate::ffmpeg::VideoStats;
+use crate::report::{Report, Section, Video};
 
-#[expect(clippy::too_many_lines)]
-pub fn send_video(
-    bot: &frankenstein::client_ureq::Bot,
-    chat_id: i64,
-    reply_params: &frankenstein::types::ReplyParameters,
-    url: &str,
-) -> anyhow::Result<()> {
-    let tempdir = tempfile::tempdir().expect("Should be able to create tempdir");
+/// Download the video with yt-dlp into the given directory
+pub fn download(url: &str, dir: &Path) -> Report {
+    let mut report = Report::default();
+    let (output, paths) = match run(url, dir, &["--format-sort=vcodec:h264,+size,+br,+res,+fps"]) {
+        Ok(run) => run,
+        Err(error) => {
+            report.error(format!("{error:#}"));
+            return report;
+        }
+    };
+    report.insert_data("files", file_names(&paths));
+    for path in paths {
+        match VideoStats::load(&path) {
+            Ok(stats) => report.push(Section::Video(Video {
+                path,
+                height: stats.height,
+                width: stats.width,
+                duration: stats.duration,
+            })),
+            Err(err) => report.error(format!(
+                "Failed to get video stats from: {} {err}",
+                path.display()
+            )),
+        }
+    }
+    add_output(&mut report, &output);
+    report
+}
+
+/// Download only the audio with yt-dlp into the given directory
+pub fn download_audio(url: &str, dir: &Path) -> Report {
+    let mut report = Report::default();
+    let format_args = [
+        "--format=bestaudio",
+        "--extract-audio",
+        "--audio-format=mp3",
+    ];
+    let (output, paths) = match run(url, dir, &format_args) {
+        Ok(run) => run,
+        Err(error) => {
+            report.error(format!("{error:#}"));
+            return report;
+        }
+    };
+    report.insert_data("files", file_names(&paths));
+    for path in paths {
+        report.push(Section::AudioFile(path));
+    }
+    add_output(&mut report, &output);
+    report
+}
 
-    let start_message = bot
-        .send_message(
-            &SendMessageParams::builder()
-                .chat_id(chat_id)
-                .reply_parameters(reply_params.clone())
-                .text("Start yt-dlp…")
-                .build(),
-        )?
-        .result
-        .message_id;
+/// Run yt-dlp in the directory and return the files it created
+fn run(url: &str, dir: &Path, format_args: &[&str]) -> anyhow::Result<(Output, Vec<PathBuf>)> {
+    run_program("yt-dlp", url, dir, format_args)
+}
+
+fn run_program(
+    program: &str,
+    url: &str,
+    dir: &Path,
+    format_args: &[&str],
+) -> anyhow::Result<(Output, Vec<PathBuf>)> {
+    // Concurrent downloads of the same url would continue each others partial files in a shared one.
+    // Not inside dir as every file there is assumed to be output.
+    let temp = dir
+        .parent()
+        .map_or_else(tempfile::tempdir, tempfile::tempdir_in)
+        .context("Should be able to create yt-dlp temp dir")?;
+    let mut temp_arg = OsString::from("--paths=temp:");
+    temp_arg.push(temp.path());
 
-    let output = std::process::Command::new("yt-dlp")
-        .current_dir(tempdir.path())
+    let output = std::process::Command::new(program)
+        .current_dir(dir)
         .arg("--embed-chapters")
         .arg("--embed-metadata")
         .arg("--embed-subs")
         .arg("--sub-langs=all")
         .arg("--sponsorblock-remove=default")
-        .arg("--paths=temp:/tmp/yt-dlp")
+        .arg(temp_arg)
         .arg("--no-progress")
         .arg("--no-playlist")
         .arg("--restrict-filenames")
         .arg("--trim-filenames=80")
-        .arg("--format-sort=vcodec:h264,+size,+br,+res,+fps")
+        .args(format_args)
         .arg(url)
         .output()
-        .expect("Should be able to spawn yt-dlp");
-
-    for entry in std::fs::read_dir(tempdir.path()).expect("Should be able to read tempdir") {
-        let path = entry
-            .expect("Should be able to read file in tempdir")
-            .path();
-
-        bot.send_chat_action(
-            &SendChatActionParams::builder()
-                .chat_id(chat_id)
-                .action(ChatAction::UploadVideo)
-                .build(),
-        )?;
+        .context("Should be able to spawn yt-dlp")?;
 
-        let stats = match VideoStats::load(&path) {
-            Ok(stats) => stats,
-            Err(err) => {
-                bot.send_message(
-                    &SendMessageParams::builder()
-                        .chat_id(chat_id)
-                        .reply_parameters(reply_params.clone())
-                        .text(format!(
-                            "Failed to get video stats from: {} {err}",
-                            path.display()
-                        ))
-                        .build(),
-                )?;
+    let paths = std::fs::read_dir(dir)
+        .context("Should be able to read yt-dlp output dir")?
+        .map(|entry| {
+            entry
+                .map(|entry| entry.path())
+                .context("Should be able to read file in yt-dlp output dir")
+        })
+        .collect::<anyhow::Result<_>>()?;
+    Ok((output, paths))
+}
 
-                continue;
-            }
-        };
+fn file_names(paths: &[PathBuf]) -> Vec<Option<String>> {
+    paths
+        .iter()
+        .map(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+        })
+        .collect()
+}
 
-        if let Err(error) = bot.send_video(
-            &SendVideoParams::builder()
-                .chat_id(chat_id)
-                .reply_parameters(reply_params.clone())
-                .width(stats.width)
-                .height(stats.height)
-                .duration(stats.duration)
-                .video(path)
-                .build(),
-        ) {
-            bot.send_message(
-                &SendMessageParams::builder()
-                    .chat_id(chat_id)
-                    .reply_parameters(reply_params.clone())
-                    .text(format!("Failed to send_video from yt-dlp output: {error}"))
-                    .build(),
-            )?;
-        }
-    }
+/// Outcome of yt-dlp after the downloaded files
+fn add_output(report: &mut Report, output: &Output) {
+    report.insert_data("success", output.status.success());
+    report.insert_data("exit_code", output.status.code());
+    report.insert_data("stdout", String::from_utf8_lossy(&output.stdout));
+    report.insert_data("stderr", String::from_utf8_lossy(&output.stderr));
 
-    if output.status.success() {
-        bot.delete_message(
-            &DeleteMessageParams::builder()
-                .chat_id(chat_id)
-                .message_id(start_message)
-                .build(),
-        )?;
-    } else {
-        bot.edit_message_text(
-            &EditMessageTextParams::builder()
-                .chat_id(chat_id)
-                .message_id(start_message)
-                .text(format!("yt-dlp {}", output.status))
-                .build(),
-        )?;
+    if !output.status.success() {
+        report.error(format!("yt-dlp {}", output.status));
     }
 
     let stdout = String::from_utf8_lossy(&output.stdout);
     if !stdout.is_empty() {
-        crate::telegram::send_code(
-            bot,
-            chat_id,
-            reply_params,
-            Some("yt-dlp stdout"),
-            Some("plaintext"),
-            &stdout,
-        )?;
+        report.code(Some("yt-dlp stdout"), Some("log"), stdout);
     }
 
     let stderr = String::from_utf8_lossy(&output.stderr);
     if !stderr.is_empty() {
-        crate::telegram::send_code(
-            bot,
-            chat_id,
-            reply_params,
-            Some("yt-dlp stderr"),
-            Some("plaintext"),
-            &stderr,
-        )?;
+        report.code(Some("yt-dlp stderr"), Some("log"), stderr);
     }
+}
 
-    Ok(())
+#[test]
+fn concurrent_runs_have_their_own_temp_dir() {
+    let media_dir = tempfile::tempdir().unwrap();
+    let media_dir = media_dir.path();
+    let temp_dirs = std::thread::scope(|scope| {
+        let runs = ["first", "second"].map(|name| {
+            let dir = media_dir.join(name);
+            std::fs::create_dir(&dir).unwrap();
+            // echo prints the arguments yt-dlp would get
+            scope.spawn(move || {
+                let (output, paths) =
+                    run_program("echo", "https://example.com/", &dir, &[]).unwrap();
+                assert!(paths.is_empty());
+                let stdout = String::from_utf8(output.stdout).unwrap();
+                let temp = stdout
+                    .split_whitespace()
+                    .find_map(|arg| arg.strip_prefix("--paths=temp:"))
+                    .unwrap()
+                    .to_owned();
+                assert!(temp.starts_with(media_dir.to_str().unwrap()));
+                assert!(!temp.starts_with(dir.to_str().unwrap()));
+                temp
+            })
+        });
+        runs.map(|run| run.join().unwrap())
+    });
+    assert_ne!(temp_dirs[0], temp_dirs[1]);
 }